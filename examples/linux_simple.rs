@@ -1,9 +1,8 @@
-use embedded_hal::blocking::delay::DelayMs;
 use linux_embedded_hal::{Delay, I2cdev, Pin};
 use nb::block;
 use si4703::{
-    reset_and_select_i2c_method1, ChannelSpacing, DeEmphasis, SeekDirection, SeekMode, Si4703,
-    Volume,
+    eh02::reset_and_select_i2c_method1, ChannelSpacing, Config, DeEmphasis, SeekDirection,
+    SeekMode, Si4703, Volume,
 };
 
 fn main() {
@@ -15,22 +14,20 @@ fn main() {
         reset_and_select_i2c_method1(&mut rst, &mut sda, &mut delay).unwrap();
     }
     let dev = I2cdev::new("/dev/i2c-1").unwrap();
-    let mut radio = Si4703::new(dev);
-    radio.enable_oscillator().unwrap();
-    // Wait for the oscillator to stabilize
-    delay.delay_ms(500_u16);
-    radio.enable().unwrap();
-    // Wait for powerup
-    delay.delay_ms(110_u16);
-
-    radio.set_volume(Volume::Dbfsm28).unwrap();
-    radio.set_deemphasis(DeEmphasis::Us50).unwrap();
-    radio.set_channel_spacing(ChannelSpacing::Khz100).unwrap();
+    let mut radio = Si4703::new_eh02(dev);
+    // Power up, verify the device and apply the configuration
+    let config = Config {
+        volume: Volume::Dbfsm28,
+        deemphasis: DeEmphasis::Us50,
+        channel_spacing: ChannelSpacing::Khz100,
+        ..Config::default()
+    };
+    radio.init(&mut delay, &config).unwrap();
     radio.unmute().unwrap();
 
-    let stc_int = Pin::new(27);
+    let mut stc_int = Pin::new(27);
     // Seek using STC interrupt pin
-    block!(radio.seek_with_stc_int_pin(SeekMode::Wrap, SeekDirection::Up, &stc_int)).unwrap();
+    block!(radio.seek_with_stc_int_pin(SeekMode::Wrap, SeekDirection::Up, &mut stc_int)).unwrap();
     let channel = radio.channel().unwrap_or(-1.0);
     println!("Found channel at {:1} MHz", channel);
 }
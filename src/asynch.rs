@@ -0,0 +1,1270 @@
+//! Asynchronous driver (`async` feature)
+//!
+//! This driver offers the same functionality as the blocking [`Si4703`]
+//! driver on top of the `embedded-hal-async` traits.
+//! Tuning and seeking do not need to be polled. Instead, they complete
+//! once the device signals the end of the operation, either through the
+//! GPIO2 STC interrupt pin or by polling the STC bit with a delay.
+//! A tuning that does not finish within 200ms or a seek that does not
+//! finish within 40s is cancelled and `Error::Timeout` is returned.
+//!
+//! These operations are cancel-safe: if a seek or tune future is dropped
+//! before completing, for example because of a timeout, the operation left
+//! running in the device is cancelled before the next seek or tune starts.
+//! It can also be cancelled explicitly with
+//! [`cancel_seek()`](struct.Si4703.html#method.cancel_seek) or
+//! [`cancel_tune()`](struct.Si4703.html#method.cancel_tune).
+//!
+//! ```no_run
+//! # async fn run<I2C, P, D>(i2c: I2C, mut stc_int: P, mut delay: D)
+//! # where
+//! #     I2C: embedded_hal_async::i2c::I2c,
+//! #     P: embedded_hal_async::digital::Wait,
+//! #     D: embedded_hal_async::delay::DelayNs,
+//! # {
+//! use si4703::{asynch::Si4703, Config, SeekDirection, SeekMode, TuneChannel, Volume};
+//!
+//! let mut radio = Si4703::new(i2c);
+//! let config = Config {
+//!     volume: Volume::Dbfsm28,
+//!     ..Config::default()
+//! };
+//! radio.init(&mut delay, &config).await.ok();
+//! radio.unmute().await.ok();
+//!
+//! let tuned = radio.tune(TuneChannel::Mhz(98.3), &mut delay).await;
+//! let found = radio
+//!     .seek_with_stc_int_pin(SeekMode::Wrap, SeekDirection::Up, &mut stc_int, &mut delay)
+//!     .await;
+//! # }
+//! ```
+//!
+//! [`Si4703`]: ../struct.Si4703.html
+
+// `embedded-hal-async` requires Rust 1.75, above the MSRV of the crate.
+#![allow(clippy::incompatible_msrv)]
+
+use crate::{
+    fields::{
+        self, Registers, OSCILLATOR_STARTUP_MS, POWERUP_MS, STC_CLEAR_TIMEOUT_MS,
+        STC_POLL_INTERVAL_MS, TUNE_TIMEOUT_MS,
+    },
+    ic,
+    init::check_id,
+    marker, private,
+    rds::decode_rds_data,
+    register_access::{from_registers, to_registers, DEVICE_ADDRESS},
+    registers::{RegisterDump, RegisterValue, WritableRegister},
+    reset::{self, ResetPin, ResetStep},
+    scan::record_station,
+    tune::get_raw_tune_channel,
+    types::PowerDownState,
+    Band, BitFlags, ChannelSpacing, ChipInfo, Config, DeEmphasis, DeviceKind, Error, ErrorWithPin,
+    Frequency, Gpio1Config, Gpio2Config, Gpio3Config, InitError, Modifier, Operation, OutputMode,
+    RdsData, RdsMode, Region, Register, Rounding, SeekDirection, SeekFmImpulseThreshold, SeekMode,
+    SeekOutcome, SeekSnrThreshold, Snapshot, SoftmuteAttenuation, SoftmuteRate, Station, Status,
+    StereoToMonoBlendLevel, TuneChannel, TuneOutcome, Volume,
+};
+use core::{
+    convert::Infallible,
+    future::{poll_fn, Future},
+    marker::PhantomData,
+    pin::pin,
+    task::Poll,
+};
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::{delay::DelayNs, digital::Wait, i2c::I2c};
+
+/// Si4703 asynchronous device driver
+///
+/// Like the blocking driver, this keeps a shadow copy of the control
+/// registers (POWERCFG to TEST1). See [`resync()`](#method.resync).
+#[derive(Debug)]
+pub struct Si4703<I2C, IC> {
+    i2c: I2C,
+    shadow: [u16; 16],
+    shadow_valid: bool,
+    /// Whether a seek or tune was started and the STC bit has not been
+    /// seen clearing yet
+    operation_pending: bool,
+    power_down_state: Option<PowerDownState>,
+    _ic: PhantomData<IC>,
+}
+
+impl<I2C, E> Si4703<I2C, ic::Si4703>
+where
+    I2C: I2c<Error = E>,
+{
+    /// Create new instance of a Si4703 device
+    pub fn new(i2c: I2C) -> Self {
+        Si4703 {
+            i2c,
+            shadow: [0; 16],
+            shadow_valid: false,
+            operation_pending: false,
+            power_down_state: None,
+            _ic: PhantomData,
+        }
+    }
+}
+
+impl<I2C, E> Si4703<I2C, ic::Si4702>
+where
+    I2C: I2c<Error = E>,
+{
+    /// Create new instance of a Si4702 device
+    pub fn new_si4702(i2c: I2C) -> Self {
+        Si4703 {
+            i2c,
+            shadow: [0; 16],
+            shadow_valid: false,
+            operation_pending: false,
+            power_down_state: None,
+            _ic: PhantomData,
+        }
+    }
+}
+
+impl<I2C, E> Si4703<I2C, ic::Si4700>
+where
+    I2C: I2c<Error = E>,
+{
+    /// Create new instance of a Si4700 device
+    pub fn new_si4700(i2c: I2C) -> Self {
+        Si4703 {
+            i2c,
+            shadow: [0; 16],
+            shadow_valid: false,
+            operation_pending: false,
+            power_down_state: None,
+            _ic: PhantomData,
+        }
+    }
+}
+
+impl<I2C, E> Si4703<I2C, ic::Si4701>
+where
+    I2C: I2c<Error = E>,
+{
+    /// Create new instance of a Si4701 device
+    pub fn new_si4701(i2c: I2C) -> Self {
+        Si4703 {
+            i2c,
+            shadow: [0; 16],
+            shadow_valid: false,
+            operation_pending: false,
+            power_down_state: None,
+            _ic: PhantomData,
+        }
+    }
+}
+
+impl<I2C, IC> Si4703<I2C, IC> {
+    /// Destroy driver instance, return I²C bus instance.
+    pub fn destroy(self) -> I2C {
+        self.i2c
+    }
+
+    fn into_ic<IC2>(self) -> Si4703<I2C, IC2> {
+        Si4703 {
+            i2c: self.i2c,
+            shadow: self.shadow,
+            shadow_valid: self.shadow_valid,
+            operation_pending: self.operation_pending,
+            power_down_state: self.power_down_state,
+            _ic: PhantomData,
+        }
+    }
+}
+
+/// Asynchronous driver for a device detected at runtime
+///
+/// See [`detect()`](fn.detect.html).
+#[derive(Debug)]
+pub enum DetectedDevice<I2C> {
+    /// Si4700 device, without RDS support
+    Si4700(Si4703<I2C, ic::Si4700>),
+    /// Si4701 device, with RDS support
+    Si4701(Si4703<I2C, ic::Si4701>),
+    /// Si4702 device, without RDS support
+    Si4702(Si4703<I2C, ic::Si4702>),
+    /// Si4703 device, with RDS support
+    Si4703(Si4703<I2C, ic::Si4703>),
+}
+
+impl<I2C> DetectedDevice<I2C> {
+    /// Destroy driver instance, return I²C bus instance.
+    pub fn destroy(self) -> I2C {
+        match self {
+            DetectedDevice::Si4700(dev) => dev.destroy(),
+            DetectedDevice::Si4701(dev) => dev.destroy(),
+            DetectedDevice::Si4702(dev) => dev.destroy(),
+            DetectedDevice::Si4703(dev) => dev.destroy(),
+        }
+    }
+}
+
+/// Detect the device and create the appropriate asynchronous driver
+///
+/// Before powerup, an Si4701 is detected as an Si4703 and an Si4700 as an
+/// Si4702. See the blocking [`detect()`](../fn.detect.html).
+pub async fn detect<I2C, E>(i2c: I2C) -> Result<DetectedDevice<I2C>, Error<E>>
+where
+    I2C: I2c<Error = E>,
+{
+    let mut dev = Si4703::new(i2c);
+    let info = fields::chip_info(&dev.read_all_registers().await?);
+    match fields::detected_device(&info)? {
+        DeviceKind::Si4700 => Ok(DetectedDevice::Si4700(dev.into_ic())),
+        DeviceKind::Si4701 => Ok(DetectedDevice::Si4701(dev.into_ic())),
+        DeviceKind::Si4703 => Ok(DetectedDevice::Si4703(dev)),
+        _ => Ok(DetectedDevice::Si4702(dev.into_ic())),
+    }
+}
+
+/// Time after which the STC bit is read again while waiting on the STC
+/// interrupt pin, in case the interrupt pulse was missed
+const STC_INT_FALLBACK_MS: u32 = 100;
+
+/// Maximum time to wait for a seek to finish.
+/// A seek takes up to 60ms per channel and the widest band (76–108 MHz)
+/// has 641 channels at 50 kHz spacing.
+const SEEK_TIMEOUT_MS: u32 = 40_000;
+
+/// Way of waiting for the STC bit to be set
+trait StcWait {
+    type Error;
+    /// Longest time a single wait can take
+    const MAX_WAIT_MS: u32;
+    async fn wait<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Self::Error>;
+}
+
+/// Polls the STC bit with a delay
+struct DelayPoll;
+
+impl StcWait for DelayPoll {
+    type Error = Infallible;
+    const MAX_WAIT_MS: u32 = STC_POLL_INTERVAL_MS;
+    async fn wait<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Self::Error> {
+        delay.delay_ms(STC_POLL_INTERVAL_MS).await;
+        Ok(())
+    }
+}
+
+struct StcIntPin<'a, P>(&'a mut P);
+
+impl<P: Wait> StcWait for StcIntPin<'_, P> {
+    type Error = P::Error;
+    const MAX_WAIT_MS: u32 = STC_INT_FALLBACK_MS;
+    /// Wait for the falling edge of the STC interrupt, which is only a
+    /// short low pulse, or until the fallback time has elapsed.
+    async fn wait<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Self::Error> {
+        let mut edge = pin!(self.0.wait_for_falling_edge());
+        let mut fallback = pin!(delay.delay_ms(STC_INT_FALLBACK_MS));
+        poll_fn(|cx| match edge.as_mut().poll(cx) {
+            Poll::Ready(result) => Poll::Ready(result),
+            Poll::Pending => fallback.as_mut().poll(cx).map(Ok),
+        })
+        .await
+    }
+}
+
+fn without_pin<E>(error: ErrorWithPin<E, Infallible>) -> Error<E> {
+    match error {
+        ErrorWithPin::I2C(e) => Error::I2C(e),
+        ErrorWithPin::Pin(e) => match e {},
+        ErrorWithPin::InvalidInputData => Error::InvalidInputData,
+        ErrorWithPin::UnsupportedDevice => Error::UnsupportedDevice,
+        ErrorWithPin::OffGrid => Error::OffGrid,
+        ErrorWithPin::Timeout => Error::Timeout,
+        ErrorWithPin::Aborted => Error::Aborted,
+        ErrorWithPin::Busy => Error::Busy,
+    }
+}
+
+impl<I2C, E, IC> Si4703<I2C, IC>
+where
+    I2C: I2c<Error = E>,
+{
+    /// Refresh the shadow copy of the control registers from the device.
+    ///
+    /// This must be called if the device has been reset or the registers
+    /// have been changed by other means since this driver last accessed them.
+    pub async fn resync(&mut self) -> Result<(), Error<E>> {
+        self.read_all_registers().await.map(|_| ())
+    }
+
+    /// Enable the oscillator.
+    ///
+    /// This must be called before enabling the device.
+    /// After calling this, a minimum of 500ms must be waited in order
+    /// for the oscillator to power up.
+    pub async fn enable_oscillator(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_oscillator(regs, true))
+            .await
+    }
+
+    /// Enable the device.
+    ///
+    /// After calling this it must be waited for the device to power up.
+    /// See: Powerup time in the datasheet.
+    /// On the Si4703, this is a maximum of 110ms.
+    pub async fn enable(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_enable(regs, true))
+            .await
+    }
+
+    /// Disable the device (power down).
+    pub async fn disable(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_enable(regs, false))
+            .await
+    }
+
+    /// Power down the device following the sequence in the programmer's guide
+    ///
+    /// See the blocking [`power_down()`](../struct.Si4703.html#method.power_down).
+    pub async fn power_down(&mut self) -> Result<(), Error<E>> {
+        self.power_down_with(false).await
+    }
+
+    async fn power_down_with(&mut self, audio_high_z: bool) -> Result<(), Error<E>> {
+        let mut regs = self.cached_registers().await?;
+        let (state, last) = fields::prepare_power_down(&mut regs, audio_high_z);
+        self.write_registers(&regs[..=last]).await?;
+        if self.power_down_state.is_none() {
+            self.power_down_state = Some(state);
+        }
+        self.update_registers(|regs| fields::set_enable(regs, false))
+            .await
+    }
+
+    /// Power up the device after a [`power_down()`](#method.power_down)
+    ///
+    /// The settings from before the power down are restored in the same
+    /// write that enables the device, then the powerup time is waited.
+    pub async fn power_up<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        let state = self.power_down_state;
+        self.update_registers(|regs| fields::power_up(regs, state.as_ref()))
+            .await?;
+        self.power_down_state = None;
+        delay.delay_ms(POWERUP_MS).await;
+        Ok(())
+    }
+
+    /// Unmute (disable mute)
+    pub async fn unmute(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_mute(regs, false))
+            .await
+    }
+
+    /// Mute (enable mute)
+    pub async fn mute(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_mute(regs, true))
+            .await
+    }
+
+    /// Enable softmute
+    pub async fn enable_softmute(
+        &mut self,
+        rate: SoftmuteRate,
+        attenuation: SoftmuteAttenuation,
+    ) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::enable_softmute(regs, rate, attenuation))
+            .await
+    }
+
+    /// Disable softmute
+    pub async fn disable_softmute(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(fields::disable_softmute).await
+    }
+
+    /// Get output mode: Stereo/Mono
+    pub async fn output_mode(&mut self) -> Result<OutputMode, Error<E>> {
+        let regs = self.read_some_registers(1).await?;
+        Ok(fields::output_mode(regs[Register::STATUSRSSI]))
+    }
+
+    /// Get the received signal strength indicator (RSSI) in dBµV
+    pub async fn rssi(&mut self) -> Result<u8, Error<E>> {
+        let regs = self.read_some_registers(1).await?;
+        Ok(fields::rssi(regs[Register::STATUSRSSI]))
+    }
+
+    /// Get the output mode (Stereo/Mono) and the RSSI in dBµV
+    /// with a single read.
+    pub async fn output_mode_and_rssi(&mut self) -> Result<(OutputMode, u8), Error<E>> {
+        let status = self.read_some_registers(1).await?[Register::STATUSRSSI];
+        Ok((fields::output_mode(status), fields::rssi(status)))
+    }
+
+    /// Get the device status
+    ///
+    /// The STATUSRSSI and READCHAN registers are read in a single transaction.
+    pub async fn status(&mut self) -> Result<Status, Error<E>> {
+        let regs = self.read_some_registers(2).await?;
+        Ok(fields::status(
+            regs[Register::STATUSRSSI],
+            regs[Register::READCHAN],
+        ))
+    }
+
+    /// Set output mode: Stereo/Mono
+    pub async fn set_output_mode(&mut self, mode: OutputMode) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_output_mode(regs, mode))
+            .await
+    }
+
+    /// Set the band, channel spacing and de-emphasis used in a region
+    pub async fn set_region(&mut self, region: Region) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_region(regs, region))
+            .await
+    }
+
+    /// Set de-emphasis
+    pub async fn set_deemphasis(&mut self, de: DeEmphasis) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_deemphasis(regs, de))
+            .await
+    }
+
+    /// Set the volume
+    ///
+    /// This will automatically activate or deactivate the extended volume
+    /// range as appropriate. The extended volume range is only available on
+    /// the Si4702/03, otherwise `Error::InvalidInputData` is returned.
+    pub async fn set_volume(&mut self, volume: Volume) -> Result<(), Error<E>>
+    where
+        IC: private::ExpectedDevice,
+    {
+        fields::check_volume(IC::DEVICE, volume)?;
+        self.update_registers(|regs| fields::set_volume(regs, volume))
+            .await
+    }
+
+    /// Set band
+    pub async fn set_band(&mut self, band: Band) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_band(regs, band))
+            .await
+    }
+
+    /// Set channel spacing
+    pub async fn set_channel_spacing(&mut self, spacing: ChannelSpacing) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_channel_spacing(regs, spacing))
+            .await
+    }
+
+    /// Enable generating STC interrupts.
+    ///
+    /// For this to be useful, gpio2 pin must be configured to be
+    /// used as STC/RDS interrupt.
+    pub async fn enable_stc_interrupts(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_stc_interrupts(regs, true))
+            .await
+    }
+
+    /// Disable generating STC interrupts.
+    pub async fn disable_stc_interrupts(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_stc_interrupts(regs, false))
+            .await
+    }
+
+    /// Set the GPIO1
+    pub async fn set_gpio1(&mut self, config: Gpio1Config) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_gpio1(regs, config))
+            .await
+    }
+
+    /// Set GPIO2 function / status
+    pub async fn set_gpio2(&mut self, config: Gpio2Config) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_gpio2(regs, config))
+            .await
+    }
+
+    /// Set the GPIO3
+    pub async fn set_gpio3(&mut self, config: Gpio3Config) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_gpio3(regs, config))
+            .await
+    }
+
+    /// Enable automatic gain control (default).
+    pub async fn enable_auto_gain_control(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_auto_gain_control(regs, true))
+            .await
+    }
+
+    /// Disable automatic gain control.
+    pub async fn disable_auto_gain_control(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_auto_gain_control(regs, false))
+            .await
+    }
+
+    /// Set the stereo to mono blend level
+    pub async fn set_stereo_to_mono_blend_level(
+        &mut self,
+        level: StereoToMonoBlendLevel,
+    ) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_stereo_to_mono_blend_level(regs, level))
+            .await
+    }
+
+    /// Configure seek RSSI, SNR and FM impulse detection thresholds
+    pub async fn configure_seek(
+        &mut self,
+        rssi_threshold: u8,
+        snr_threshold: SeekSnrThreshold,
+        fm_impulse_threshold: SeekFmImpulseThreshold,
+    ) -> Result<(), Error<E>> {
+        let thresholds = fields::seek_thresholds_mask(snr_threshold, fm_impulse_threshold)?;
+        self.update_registers(|regs| fields::configure_seek(regs, rssi_threshold, thresholds))
+            .await
+    }
+
+    /// Get whether the oscillator is enabled.
+    pub async fn is_oscillator_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_oscillator_enabled(
+            &self.cached_registers().await?,
+        ))
+    }
+
+    /// Get whether the device is enabled (powered up).
+    pub async fn is_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_enabled(&self.cached_registers().await?))
+    }
+
+    /// Get whether the audio is muted.
+    pub async fn is_muted(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_muted(&self.cached_registers().await?))
+    }
+
+    /// Get the softmute rate and attenuation
+    ///
+    /// Returns `None` if softmute is disabled.
+    pub async fn softmute(
+        &mut self,
+    ) -> Result<Option<(SoftmuteRate, SoftmuteAttenuation)>, Error<E>> {
+        Ok(fields::softmute(&self.cached_registers().await?))
+    }
+
+    /// Get the configured output mode: Stereo/Mono
+    ///
+    /// This is `Mono` if mono output has been forced. See `output_mode()`
+    /// for the mode actually being received.
+    pub async fn configured_output_mode(&mut self) -> Result<OutputMode, Error<E>> {
+        Ok(fields::configured_output_mode(
+            &self.cached_registers().await?,
+        ))
+    }
+
+    /// Get the region matching the configured band, channel spacing
+    /// and de-emphasis, if any
+    pub async fn region(&mut self) -> Result<Option<Region>, Error<E>> {
+        Ok(fields::region(&self.cached_registers().await?))
+    }
+
+    /// Get the de-emphasis
+    pub async fn deemphasis(&mut self) -> Result<DeEmphasis, Error<E>> {
+        Ok(fields::deemphasis(&self.cached_registers().await?))
+    }
+
+    /// Get the volume
+    pub async fn volume(&mut self) -> Result<Volume, Error<E>> {
+        Ok(fields::volume(&self.cached_registers().await?))
+    }
+
+    /// Get the band
+    pub async fn band(&mut self) -> Result<Band, Error<E>> {
+        Ok(fields::band(&self.cached_registers().await?))
+    }
+
+    /// Get the channel spacing
+    pub async fn channel_spacing(&mut self) -> Result<ChannelSpacing, Error<E>> {
+        Ok(fields::channel_spacing(&self.cached_registers().await?))
+    }
+
+    /// Get whether generating STC interrupts is enabled.
+    pub async fn is_stc_interrupts_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_stc_interrupts_enabled(
+            &self.cached_registers().await?,
+        ))
+    }
+
+    /// Get the GPIO1 configuration
+    pub async fn gpio1(&mut self) -> Result<Gpio1Config, Error<E>> {
+        Ok(fields::gpio1(&self.cached_registers().await?))
+    }
+
+    /// Get the GPIO2 configuration
+    pub async fn gpio2(&mut self) -> Result<Gpio2Config, Error<E>> {
+        Ok(fields::gpio2(&self.cached_registers().await?))
+    }
+
+    /// Get the GPIO3 configuration
+    pub async fn gpio3(&mut self) -> Result<Gpio3Config, Error<E>> {
+        Ok(fields::gpio3(&self.cached_registers().await?))
+    }
+
+    /// Get whether automatic gain control is enabled.
+    pub async fn is_auto_gain_control_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_auto_gain_control_enabled(
+            &self.cached_registers().await?,
+        ))
+    }
+
+    /// Get the stereo to mono blend level
+    pub async fn blend_level(&mut self) -> Result<StereoToMonoBlendLevel, Error<E>> {
+        Ok(fields::blend_level(&self.cached_registers().await?))
+    }
+
+    /// Get the seek RSSI, SNR and FM impulse detection thresholds
+    ///
+    /// Returns the (RSSI, SNR, FM impulse) thresholds as a tuple
+    pub async fn seek_config(
+        &mut self,
+    ) -> Result<(u8, SeekSnrThreshold, SeekFmImpulseThreshold), Error<E>> {
+        Ok(fields::seek_config(&self.cached_registers().await?))
+    }
+
+    /// Apply the whole configuration
+    ///
+    /// All control registers (POWERCFG to TEST1) are written in a single
+    /// transaction. Settings not part of the configuration like enable,
+    /// mute or the output mode are kept.
+    pub async fn apply_config(&mut self, config: &Config) -> Result<(), Error<E>>
+    where
+        IC: private::ExpectedDevice,
+    {
+        let thresholds = fields::check_config(IC::DEVICE, config)?;
+        self.update_registers(|regs| fields::apply_config(regs, config, thresholds))
+            .await
+    }
+
+    /// Read the whole configuration from the device
+    ///
+    /// This also refreshes the shadow copy of the control registers.
+    pub async fn read_config(&mut self) -> Result<Config, Error<E>> {
+        let regs = self.read_all_registers().await?;
+        Ok(fields::config(&regs))
+    }
+
+    /// Change several settings in a single transaction
+    ///
+    /// The changes done in the closure are collected and written at once.
+    /// Only the registers from POWERCFG up to the last changed one are
+    /// written. Nothing is written if nothing changed.
+    pub async fn modify<F>(&mut self, f: F) -> Result<(), Error<E>>
+    where
+        F: FnOnce(&mut Modifier<IC>),
+    {
+        let original = self.cached_registers().await?;
+        let mut modifier = Modifier::new(original);
+        f(&mut modifier);
+        match modifier.finish(&original)? {
+            (regs, Some(last)) => self.write_registers(&regs[..=last]).await,
+            (_, None) => Ok(()),
+        }
+    }
+
+    /// Read the channel
+    pub async fn channel(&mut self) -> Result<f32, Error<E>> {
+        let sysconfig2 = self.cached_registers().await?[Register::SYSCONFIG2];
+        let readchan = self.read_some_registers(2).await?[Register::READCHAN];
+        Ok(fields::channel_mhz(sysconfig2, readchan))
+    }
+
+    /// Read the channel frequency
+    pub async fn channel_khz(&mut self) -> Result<Frequency, Error<E>> {
+        let sysconfig2 = self.cached_registers().await?[Register::SYSCONFIG2];
+        let readchan = self.read_some_registers(2).await?[Register::READCHAN];
+        Ok(fields::channel_khz(sysconfig2, readchan))
+    }
+
+    /// Get the device ID
+    ///
+    /// Returns the (part number, manufacturer ID) as a tuple
+    pub async fn device_id(&mut self) -> Result<(u8, u16), Error<E>> {
+        let regs = self.read_all_registers().await?;
+        Ok(fields::device_id(regs[Register::DEVICE_ID]))
+    }
+
+    /// Get the chip ID
+    ///
+    /// Returns the (revision, device, firmware) as a tuple
+    pub async fn chip_id(&mut self) -> Result<(u8, u8, u8), Error<E>> {
+        let regs = self.read_all_registers().await?;
+        Ok(fields::chip_id(regs[Register::CHIP_ID]))
+    }
+
+    /// Get the chip identification
+    ///
+    /// The Si4702/03 only report their device and firmware after powerup.
+    pub async fn chip_info(&mut self) -> Result<ChipInfo, Error<E>> {
+        let regs = self.read_all_registers().await?;
+        Ok(fields::chip_info(&regs))
+    }
+
+    /// Read all the registers
+    ///
+    /// The returned [`RegisterDump`](../registers/struct.RegisterDump.html) can be printed to
+    /// show every field of every register, which is useful for diagnostics.
+    pub async fn read_registers(&mut self) -> Result<RegisterDump, Error<E>> {
+        Ok(RegisterDump::from(self.read_all_registers().await?))
+    }
+
+    /// Read a register
+    ///
+    /// See the [`registers`](../registers/index.html) module.
+    pub async fn read_register<R: RegisterValue>(&mut self) -> Result<R, Error<E>> {
+        let regs = if R::ADDRESS >= Register::STATUSRSSI {
+            self.read_some_registers(R::ADDRESS - Register::STATUSRSSI + 1)
+                .await?
+        } else {
+            self.read_all_registers().await?
+        };
+        Ok(R::from(regs[R::ADDRESS]))
+    }
+
+    /// Write a register
+    ///
+    /// The registers from POWERCFG up to the given one are written.
+    /// The values for the registers before it are taken from the cache.
+    /// See the [`registers`](../registers/index.html) module.
+    pub async fn write_register<R: WritableRegister>(&mut self, value: R) -> Result<(), Error<E>> {
+        self.update_registers(|regs| {
+            regs[R::ADDRESS] = value.into();
+            R::ADDRESS
+        })
+        .await
+    }
+
+    /// Take a snapshot of the device configuration
+    ///
+    /// See [`Snapshot`].
+    pub async fn snapshot(&mut self) -> Result<Snapshot, Error<E>> {
+        let regs = self.read_all_registers().await?;
+        Ok(Snapshot::from_registers(&regs))
+    }
+
+    /// Restore a snapshot of the device configuration
+    ///
+    /// This follows the powerup sequence, waiting for the oscillator and
+    /// the powerup with the provided delay, and tunes to the snapshot
+    /// channel if the device was enabled when the snapshot was taken.
+    /// If the tuning does not finish within 200ms, it is cancelled and
+    /// `Error::Timeout` is returned.
+    pub async fn restore<D: DelayNs>(
+        &mut self,
+        snapshot: &Snapshot,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        let plan = snapshot.restore_plan(&self.read_all_registers().await?);
+        if let Some(oscillator) = plan.oscillator {
+            self.write_registers(&oscillator[..=plan.last]).await?;
+            delay.delay_ms(OSCILLATOR_STARTUP_MS).await;
+        }
+        self.write_registers(&plan.registers[..=plan.last]).await?;
+        if plan.wait_powerup {
+            delay.delay_ms(POWERUP_MS).await;
+        }
+        match plan.channel {
+            Some(channel) => self
+                .tune(TuneChannel::Raw(channel), delay)
+                .await
+                .map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    /// Tune to a certain frequency polling the STC bit.
+    ///
+    /// The STC bit is read every 10ms using the provided delay.
+    /// Frequencies between channels select the channel below.
+    /// Returns the outcome of the tuning.
+    /// If the tuning does not finish within 200ms, it is cancelled and
+    /// `Error::Timeout` is returned.
+    pub async fn tune<D: DelayNs>(
+        &mut self,
+        channel: TuneChannel,
+        delay: &mut D,
+    ) -> Result<TuneOutcome, Error<E>> {
+        self.tune_rounded(channel, Rounding::Floor, delay).await
+    }
+
+    /// Tune to a certain frequency using the given rounding policy
+    ///
+    /// See the blocking [`tune_rounded()`](../struct.Si4703.html#method.tune_rounded).
+    /// Returns the outcome of the tuning.
+    pub async fn tune_rounded<D: DelayNs>(
+        &mut self,
+        channel: TuneChannel,
+        rounding: Rounding,
+        delay: &mut D,
+    ) -> Result<TuneOutcome, Error<E>> {
+        self.finish_pending_operation(delay).await?;
+        let mut regs = self.cached_registers().await?;
+        let raw = get_raw_tune_channel(regs[Register::SYSCONFIG2], channel, rounding)?;
+        let last = fields::start_tune(&mut regs, raw);
+        self.operation_pending = true;
+        self.write_registers(&regs[..=last]).await?;
+        self.complete_operation(Operation::Tune, DelayPoll, delay)
+            .await
+            .map(|regs| fields::tune_outcome(&regs))
+            .map_err(without_pin)
+    }
+
+    /// Tune using GPIO2 as STC interrupt pin (recommended)
+    ///
+    /// This will configure GPIO2 as STC interrupt pin and enable
+    /// STC interrupts if appropriate.
+    /// The provided delay is used to wait for the STC bit to clear and to
+    /// read the STC bit again every 100ms in case an interrupt was missed.
+    /// Frequencies between channels select the channel below.
+    /// Returns the outcome of the tuning.
+    pub async fn tune_with_stc_int_pin<P: Wait, D: DelayNs>(
+        &mut self,
+        channel: TuneChannel,
+        stc_int_pin: &mut P,
+        delay: &mut D,
+    ) -> Result<TuneOutcome, ErrorWithPin<E, P::Error>> {
+        self.tune_rounded_with_stc_int_pin(channel, Rounding::Floor, stc_int_pin, delay)
+            .await
+    }
+
+    /// Tune using GPIO2 as STC interrupt pin and the given rounding policy
+    ///
+    /// See [`tune_with_stc_int_pin()`](#method.tune_with_stc_int_pin).
+    /// Returns the outcome of the tuning.
+    pub async fn tune_rounded_with_stc_int_pin<P: Wait, D: DelayNs>(
+        &mut self,
+        channel: TuneChannel,
+        rounding: Rounding,
+        stc_int_pin: &mut P,
+        delay: &mut D,
+    ) -> Result<TuneOutcome, ErrorWithPin<E, P::Error>> {
+        self.finish_pending_operation(delay).await?;
+        let mut regs = self.cached_registers().await?;
+        let raw = get_raw_tune_channel(regs[Register::SYSCONFIG2], channel, rounding)?;
+        let mut last = fields::start_tune(&mut regs, raw);
+        if fields::enable_stc_int_pin(&mut regs) {
+            last = Register::SYSCONFIG1;
+        }
+        self.operation_pending = true;
+        self.write_registers(&regs[..=last]).await?;
+        self.complete_operation(Operation::Tune, StcIntPin(stc_int_pin), delay)
+            .await
+            .map(|regs| fields::tune_outcome(&regs))
+    }
+
+    /// Seek polling the STC bit.
+    ///
+    /// The STC bit is read every 10ms using the provided delay.
+    /// Returns the outcome of the seek.
+    /// If the seek does not finish within 40s, it is cancelled and
+    /// `Error::Timeout` is returned.
+    pub async fn seek<D: DelayNs>(
+        &mut self,
+        mode: SeekMode,
+        direction: SeekDirection,
+        delay: &mut D,
+    ) -> Result<SeekOutcome, Error<E>> {
+        self.finish_pending_operation(delay).await?;
+        self.operation_pending = true;
+        self.update_registers(|regs| fields::start_seek(regs, mode, direction))
+            .await?;
+        self.complete_operation(Operation::Seek, DelayPoll, delay)
+            .await
+            .map(|regs| fields::seek_outcome(&regs))
+            .map_err(without_pin)
+    }
+
+    /// Seek using GPIO2 as STC interrupt pin (recommended)
+    ///
+    /// This will configure GPIO2 as STC interrupt pin and enable
+    /// STC interrupts if appropriate.
+    /// The provided delay is used to wait for the STC bit to clear and to
+    /// read the STC bit again every 100ms in case an interrupt was missed.
+    /// Returns the outcome of the seek.
+    pub async fn seek_with_stc_int_pin<P: Wait, D: DelayNs>(
+        &mut self,
+        mode: SeekMode,
+        direction: SeekDirection,
+        stc_int_pin: &mut P,
+        delay: &mut D,
+    ) -> Result<SeekOutcome, ErrorWithPin<E, P::Error>> {
+        self.finish_pending_operation(delay).await?;
+        self.operation_pending = true;
+        self.update_registers(|regs| {
+            let last = fields::start_seek(regs, mode, direction);
+            if fields::enable_stc_int_pin(regs) {
+                Register::SYSCONFIG1
+            } else {
+                last
+            }
+        })
+        .await?;
+        self.complete_operation(Operation::Seek, StcIntPin(stc_int_pin), delay)
+            .await
+            .map(|regs| fields::seek_outcome(&regs))
+    }
+
+    /// Scan the whole band for receivable stations polling the STC bit
+    ///
+    /// The STC bit is read every 10ms using the provided delay.
+    /// See the blocking [`scan_band()`](../struct.Si4703.html#method.scan_band).
+    /// Returns the number of stations stored.
+    pub async fn scan_band<D: DelayNs, F: FnMut(Frequency)>(
+        &mut self,
+        stations: &mut [Station],
+        delay: &mut D,
+        mut progress: F,
+    ) -> Result<usize, Error<E>> {
+        if stations.is_empty() {
+            return Ok(0);
+        }
+        self.tune_rounded(TuneChannel::Raw(0), Rounding::Floor, delay)
+            .await?;
+        let mut found = 0;
+        loop {
+            let outcome = self
+                .seek(SeekMode::NoWrap, SeekDirection::Up, delay)
+                .await?;
+            progress(outcome.frequency);
+            if record_station(&outcome, stations, &mut found) {
+                return Ok(found);
+            }
+        }
+    }
+
+    /// Cancel an ongoing seek
+    ///
+    /// The SEEK bit is cleared and the STC bit is polled every 10ms using
+    /// the provided delay until the device has cleared it.
+    pub async fn cancel_seek<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        self.cancel_operation(Register::POWERCFG, BitFlags::SEEK, delay)
+            .await
+    }
+
+    /// Cancel an ongoing tuning
+    ///
+    /// The TUNE bit is cleared and the STC bit is polled every 10ms using
+    /// the provided delay until the device has cleared it.
+    pub async fn cancel_tune<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        self.cancel_operation(Register::CHANNEL, BitFlags::TUNE, delay)
+            .await
+    }
+
+    async fn cancel_operation<D: DelayNs>(
+        &mut self,
+        register: usize,
+        bitflag: u16,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        self.update_registers(|regs| {
+            regs[register] &= !bitflag;
+            register
+        })
+        .await?;
+        self.wait_for_stc_to_clear(delay).await
+    }
+
+    /// Cancel an operation whose future was dropped before completing.
+    async fn finish_pending_operation<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        if !self.operation_pending {
+            return Ok(());
+        }
+        self.update_registers(|regs| {
+            regs[Register::POWERCFG] &= !BitFlags::SEEK;
+            regs[Register::CHANNEL] &= !BitFlags::TUNE;
+            Register::CHANNEL
+        })
+        .await?;
+        self.wait_for_stc_to_clear(delay).await
+    }
+
+    /// Poll the STC bit every 10ms until the device clears it.
+    ///
+    /// Returns `Error::Timeout` if it is still set after 100ms.
+    async fn wait_for_stc_to_clear<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        let mut waited_ms = 0;
+        while (self.read_some_registers(1).await?[Register::STATUSRSSI] & BitFlags::STC) != 0 {
+            if waited_ms >= STC_CLEAR_TIMEOUT_MS {
+                return Err(Error::Timeout);
+            }
+            delay.delay_ms(STC_POLL_INTERVAL_MS).await;
+            waited_ms += STC_POLL_INTERVAL_MS;
+        }
+        self.operation_pending = false;
+        Ok(())
+    }
+
+    /// Wait for an operation to complete and clear its SEEK/TUNE bit.
+    ///
+    /// The operation is cancelled and `Error::Timeout` is returned if it
+    /// does not complete in time.
+    async fn complete_operation<W: StcWait, D: DelayNs>(
+        &mut self,
+        operation: Operation,
+        mut stc_wait: W,
+        delay: &mut D,
+    ) -> Result<Registers, ErrorWithPin<E, W::Error>> {
+        let (register, bitflag) = fields::operation_bit(operation);
+        let timeout_ms = match operation {
+            Operation::Tune => TUNE_TIMEOUT_MS,
+            Operation::Seek => SEEK_TIMEOUT_MS,
+        };
+        let mut waited_ms = 0;
+        let mut completed = loop {
+            let regs = self.read_some_registers(2).await?;
+            if (regs[Register::STATUSRSSI] & BitFlags::STC) != 0 {
+                break regs;
+            }
+            if waited_ms >= timeout_ms {
+                self.cancel_operation(register, bitflag, delay).await?;
+                return Err(ErrorWithPin::Timeout);
+            }
+            stc_wait.wait(delay).await.map_err(ErrorWithPin::Pin)?;
+            waited_ms += W::MAX_WAIT_MS;
+        };
+        completed[Register::SYSCONFIG2] = self.shadow[Register::SYSCONFIG2];
+        self.update_registers(|regs| {
+            regs[register] &= !bitflag;
+            register
+        })
+        .await?;
+        self.wait_for_stc_to_clear(delay).await?;
+        Ok(completed)
+    }
+}
+
+impl<I2C, E, IC> Si4703<I2C, IC>
+where
+    I2C: I2c<Error = E>,
+    IC: private::ExpectedDevice,
+{
+    /// Power up the device and apply the configuration
+    ///
+    /// This performs the powerup sequence waiting with the provided delay,
+    /// verifies the device ID and chip ID and applies the configuration.
+    /// See the blocking [`init()`](../struct.Si4703.html#method.init).
+    pub async fn init<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        config: &Config,
+    ) -> Result<(), InitError<E>> {
+        let thresholds =
+            fields::check_config(IC::DEVICE, config).map_err(InitError::ApplyConfig)?;
+        self.read_all_registers()
+            .await
+            .map_err(InitError::EnableOscillator)?;
+        self.enable_oscillator()
+            .await
+            .map_err(InitError::EnableOscillator)?;
+        delay.delay_ms(OSCILLATOR_STARTUP_MS).await;
+        self.enable().await.map_err(InitError::Enable)?;
+        delay.delay_ms(POWERUP_MS).await;
+        let regs = self.read_all_registers().await.map_err(InitError::ReadId)?;
+        check_id::<IC, E>(&regs)?;
+        self.update_registers(|regs| fields::apply_config(regs, config, thresholds))
+            .await
+            .map_err(InitError::ApplyConfig)
+    }
+
+    /// Reset the device, select I2C communication, power it up and apply
+    /// the configuration
+    ///
+    /// This performs the reset and I2C selection (method 1) waiting with
+    /// the provided delay, sets the SDA pin high and calls
+    /// [`init()`](#method.init). Any ongoing seek or tune and the settings
+    /// saved by [`power_down()`](#method.power_down) are forgotten after
+    /// the reset.
+    /// See the blocking [`init_with_reset()`](../struct.Si4703.html#method.init_with_reset).
+    pub async fn init_with_reset<PinE, RST, SDA, D>(
+        &mut self,
+        rst: &mut RST,
+        sda: &mut SDA,
+        delay: &mut D,
+        config: &Config,
+    ) -> Result<(), InitError<E, PinE>>
+    where
+        RST: OutputPin<Error = PinE>,
+        SDA: OutputPin<Error = PinE>,
+        D: DelayNs,
+    {
+        for step in reset::METHOD1 {
+            match step {
+                ResetStep::Set(ResetPin::Rst, high) => rst.set_state(high.into()),
+                ResetStep::Set(_, high) => sda.set_state(high.into()),
+                ResetStep::Wait(ms) => {
+                    delay.delay_ms(ms).await;
+                    Ok(())
+                }
+            }
+            .map_err(InitError::Reset)?;
+        }
+        sda.set_high().map_err(InitError::Reset)?;
+        self.operation_pending = false;
+        self.power_down_state = None;
+        self.shadow_valid = false;
+        self.init(delay, config)
+            .await
+            .map_err(InitError::with_pin_error)
+    }
+}
+
+impl<I2C, E, IC> Si4703<I2C, IC>
+where
+    I2C: I2c<Error = E>,
+    IC: marker::WithAudioHighZ,
+{
+    /// Power down the device setting the audio outputs to high impedance
+    ///
+    /// See the blocking [`power_down_with_audio_high_z()`](../struct.Si4703.html#method.power_down_with_audio_high_z).
+    pub async fn power_down_with_audio_high_z(&mut self) -> Result<(), Error<E>> {
+        self.power_down_with(true).await
+    }
+
+    /// Enable the audio High-Z.
+    ///
+    /// This must be called before enabling the device.
+    pub async fn enable_audio_high_z(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_audio_high_z(regs, true))
+            .await
+    }
+
+    /// Disable the audio High-Z.
+    ///
+    /// This must be called before enabling the device.
+    pub async fn disable_audio_high_z(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_audio_high_z(regs, false))
+            .await
+    }
+
+    /// Get whether the audio High-Z is enabled.
+    pub async fn is_audio_high_z_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_audio_high_z_enabled(
+            &self.cached_registers().await?,
+        ))
+    }
+}
+
+impl<I2C, E, IC> Si4703<I2C, IC>
+where
+    I2C: I2c<Error = E>,
+    IC: marker::WithRds,
+{
+    /// Enable RDS.
+    pub async fn enable_rds(&mut self, mode: RdsMode) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::enable_rds(regs, mode))
+            .await
+    }
+
+    /// Disable RDS.
+    pub async fn disable_rds(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(fields::disable_rds).await
+    }
+
+    /// Enable RDS interrupts.
+    pub async fn enable_rds_interrupts(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_rds_interrupts(regs, true))
+            .await
+    }
+
+    /// Disable RDS interrupts.
+    pub async fn disable_rds_interrupts(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_rds_interrupts(regs, false))
+            .await
+    }
+
+    /// Get whether RDS is enabled.
+    pub async fn is_rds_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_rds_enabled(&self.cached_registers().await?))
+    }
+
+    /// Get the RDS mode
+    pub async fn rds_mode(&mut self) -> Result<RdsMode, Error<E>> {
+        Ok(fields::rds_mode(&self.cached_registers().await?))
+    }
+
+    /// Get whether RDS interrupts are enabled.
+    pub async fn is_rds_interrupts_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_rds_interrupts_enabled(
+            &self.cached_registers().await?,
+        ))
+    }
+
+    /// Get whether a new RDS group is ready.
+    pub async fn rds_ready(&mut self) -> Result<bool, Error<E>> {
+        let regs = self.read_some_registers(1).await?;
+        Ok((regs[Register::STATUSRSSI] & BitFlags::RDSR) != 0)
+    }
+
+    /// Get RDS synchronization status (only available in RDS verbose mode).
+    pub async fn rds_synchronized(&mut self) -> Result<bool, Error<E>> {
+        let regs = self.read_some_registers(1).await?;
+        Ok((regs[Register::STATUSRSSI] & BitFlags::RDSS) != 0)
+    }
+
+    /// Get RDS data.
+    pub async fn rds_data(&mut self) -> Result<RdsData, Error<E>> {
+        let regs = self.read_some_registers(6).await?;
+        Ok(decode_rds_data(&regs))
+    }
+}
+
+impl<I2C, E, IC> Si4703<I2C, IC>
+where
+    I2C: I2c<Error = E>,
+{
+    async fn cached_registers(&mut self) -> Result<[u16; 16], Error<E>> {
+        if !self.shadow_valid {
+            self.read_all_registers().await?;
+        }
+        Ok(self.shadow)
+    }
+
+    async fn update_registers<F>(&mut self, f: F) -> Result<(), Error<E>>
+    where
+        F: FnOnce(&mut [u16; 16]) -> usize,
+    {
+        let mut regs = self.cached_registers().await?;
+        let last = f(&mut regs);
+        self.write_registers(&regs[..=last]).await
+    }
+
+    async fn read_some_registers(&mut self, count: usize) -> Result<[u16; 16], Error<E>> {
+        const OFFSET: usize = 0xA;
+        let mut data = [0; 32];
+        self.i2c
+            .read(DEVICE_ADDRESS, &mut data[..count * 2])
+            .await
+            .map_err(Error::I2C)?;
+        Ok(to_registers(data, OFFSET))
+    }
+
+    async fn read_all_registers(&mut self) -> Result<[u16; 16], Error<E>> {
+        const OFFSET: usize = 0xA;
+        let mut data = [0; 32];
+        self.i2c
+            .read(DEVICE_ADDRESS, &mut data)
+            .await
+            .map_err(Error::I2C)?;
+        let registers = to_registers(data, OFFSET);
+        self.shadow[Register::POWERCFG..=Register::TEST1]
+            .copy_from_slice(&registers[Register::POWERCFG..=Register::TEST1]);
+        self.shadow_valid = true;
+        Ok(registers)
+    }
+
+    async fn write_registers(&mut self, registers: &[u16]) -> Result<(), Error<E>> {
+        const OFFSET: usize = 0x2;
+        let data = from_registers(registers, OFFSET);
+        self.i2c
+            .write(DEVICE_ADDRESS, &data[..((registers.len() - OFFSET) * 2)])
+            .await
+            .map_err(Error::I2C)?;
+        let last = registers.len() - 1;
+        self.shadow[Register::POWERCFG..=last].copy_from_slice(&registers[Register::POWERCFG..]);
+        Ok(())
+    }
+}
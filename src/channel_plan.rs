@@ -0,0 +1,258 @@
+use crate::{Band, ChannelSpacing, Error, Frequency, Region, Rounding};
+
+/// Channel plan defined by a band and a channel spacing
+///
+/// Converts between raw channel values as used in the CHANNEL and
+/// READCHAN registers and frequencies without communicating with the device.
+///
+/// ```
+/// use si4703::{Band, ChannelPlan, ChannelSpacing, Frequency};
+///
+/// let plan = ChannelPlan::new(Band::Mhz875_108, ChannelSpacing::Khz100);
+/// assert_eq!(206, plan.channel_count());
+/// assert_eq!(Some(Frequency::from_khz(98_300)), plan.frequency_of(108));
+/// assert_eq!(Some(108), plan.raw_of(Frequency::from_khz(98_300)));
+/// assert!(!plan.contains(Frequency::from_khz(98_350)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelPlan {
+    /// Band
+    pub band: Band,
+    /// Channel spacing
+    pub spacing: ChannelSpacing,
+}
+
+impl ChannelPlan {
+    /// Create a channel plan
+    pub fn new(band: Band, spacing: ChannelSpacing) -> Self {
+        ChannelPlan { band, spacing }
+    }
+
+    /// Lowest frequency of the band
+    pub fn band_min(&self) -> Frequency {
+        Frequency::from_khz(self.band_limits_khz().0)
+    }
+
+    /// Highest frequency of the band
+    pub fn band_max(&self) -> Frequency {
+        Frequency::from_khz(self.band_limits_khz().1)
+    }
+
+    /// Channel spacing in kHz
+    pub fn spacing_khz(&self) -> u32 {
+        match self.spacing {
+            ChannelSpacing::Khz200 => 200,
+            ChannelSpacing::Khz100 => 100,
+            ChannelSpacing::Khz50 => 50,
+        }
+    }
+
+    /// Number of channels in the band
+    pub fn channel_count(&self) -> u16 {
+        (self.last_raw() + 1) as u16
+    }
+
+    /// Frequency of a raw channel value
+    ///
+    /// Returns `None` if the channel is outside of the band.
+    pub fn frequency_of(&self, raw: u16) -> Option<Frequency> {
+        if raw < self.channel_count() {
+            Some(self.frequency_of_unchecked(raw))
+        } else {
+            None
+        }
+    }
+
+    /// Raw channel value of a frequency
+    ///
+    /// Returns `None` if the frequency is outside of the band or
+    /// not on the channel spacing grid.
+    pub fn raw_of(&self, frequency: Frequency) -> Option<u16> {
+        self.round::<()>(frequency.khz(), Rounding::Strict).ok()
+    }
+
+    /// Whether the frequency is a channel of this plan
+    pub fn contains(&self, frequency: Frequency) -> bool {
+        self.raw_of(frequency).is_some()
+    }
+
+    /// Iterate over the frequencies of all channels in the band
+    pub fn iter(&self) -> Channels {
+        Channels {
+            plan: *self,
+            next: 0,
+            end: self.channel_count(),
+        }
+    }
+
+    /// Frequency of a raw channel value without checking the band limits
+    pub(crate) fn frequency_of_unchecked(&self, raw: u16) -> Frequency {
+        let (min, _) = self.band_limits_khz();
+        Frequency::from_khz(min + u32::from(raw) * self.spacing_khz())
+    }
+
+    /// Raw channel value of a frequency in kHz using the rounding policy
+    pub(crate) fn round<E>(&self, khz: u32, rounding: Rounding) -> Result<u16, Error<E>> {
+        let (min, max) = self.band_limits_khz();
+        if khz < min || khz > max {
+            return Err(Error::InvalidInputData);
+        }
+        let spacing = self.spacing_khz();
+        let offset = khz - min;
+        let floor = offset / spacing;
+        let raw = match rounding {
+            Rounding::Floor => floor,
+            Rounding::Nearest => ((offset + spacing / 2) / spacing).min(self.last_raw()),
+            Rounding::Strict if floor * spacing != offset => return Err(Error::OffGrid),
+            Rounding::Strict => floor,
+        };
+        Ok(raw as u16)
+    }
+
+    fn band_limits_khz(&self) -> (u32, u32) {
+        match self.band {
+            Band::Mhz875_108 => (87_500, 108_000),
+            Band::Mhz76_108 => (76_000, 108_000),
+            Band::Mhz76_90 => (76_000, 90_000),
+        }
+    }
+
+    fn last_raw(&self) -> u32 {
+        let (min, max) = self.band_limits_khz();
+        (max - min) / self.spacing_khz()
+    }
+}
+
+impl From<Region> for ChannelPlan {
+    fn from(region: Region) -> Self {
+        ChannelPlan::new(region.band(), region.channel_spacing())
+    }
+}
+
+impl IntoIterator for ChannelPlan {
+    type Item = Frequency;
+    type IntoIter = Channels;
+
+    fn into_iter(self) -> Channels {
+        self.iter()
+    }
+}
+
+/// Iterator over the channels of a [`ChannelPlan`](struct.ChannelPlan.html)
+#[derive(Debug, Clone)]
+pub struct Channels {
+    plan: ChannelPlan,
+    next: u16,
+    end: u16,
+}
+
+impl Iterator for Channels {
+    type Item = Frequency;
+
+    fn next(&mut self) -> Option<Frequency> {
+        if self.next < self.end {
+            let frequency = self.plan.frequency_of_unchecked(self.next);
+            self.next += 1;
+            Some(frequency)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = usize::from(self.end - self.next);
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for Channels {
+    fn next_back(&mut self) -> Option<Frequency> {
+        if self.next < self.end {
+            self.end -= 1;
+            Some(self.plan.frequency_of_unchecked(self.end))
+        } else {
+            None
+        }
+    }
+}
+
+impl ExactSizeIterator for Channels {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(band: Band, spacing: ChannelSpacing) -> ChannelPlan {
+        ChannelPlan::new(band, spacing)
+    }
+
+    #[test]
+    fn can_count_channels() {
+        assert_eq!(
+            103,
+            plan(Band::Mhz875_108, ChannelSpacing::Khz200).channel_count()
+        );
+        assert_eq!(
+            206,
+            plan(Band::Mhz875_108, ChannelSpacing::Khz100).channel_count()
+        );
+        assert_eq!(
+            411,
+            plan(Band::Mhz875_108, ChannelSpacing::Khz50).channel_count()
+        );
+        assert_eq!(
+            641,
+            plan(Band::Mhz76_108, ChannelSpacing::Khz50).channel_count()
+        );
+        assert_eq!(
+            141,
+            plan(Band::Mhz76_90, ChannelSpacing::Khz100).channel_count()
+        );
+    }
+
+    #[test]
+    fn can_get_frequency_of_raw() {
+        let plan = plan(Band::Mhz875_108, ChannelSpacing::Khz200);
+        assert_eq!(Some(Frequency::from_khz(87_500)), plan.frequency_of(0));
+        assert_eq!(Some(Frequency::from_khz(107_900)), plan.frequency_of(102));
+        assert_eq!(None, plan.frequency_of(103));
+    }
+
+    #[test]
+    fn can_get_raw_of_frequency() {
+        let plan = plan(Band::Mhz76_90, ChannelSpacing::Khz50);
+        assert_eq!(Some(0), plan.raw_of(Frequency::from_khz(76_000)));
+        assert_eq!(Some(280), plan.raw_of(Frequency::from_khz(90_000)));
+        assert_eq!(None, plan.raw_of(Frequency::from_khz(76_025)));
+        assert_eq!(None, plan.raw_of(Frequency::from_khz(90_050)));
+        assert_eq!(None, plan.raw_of(Frequency::from_khz(75_950)));
+    }
+
+    #[test]
+    fn contains_only_channels_in_band() {
+        let plan = plan(Band::Mhz875_108, ChannelSpacing::Khz200);
+        assert!(plan.contains(Frequency::from_khz(87_700)));
+        assert!(!plan.contains(Frequency::from_khz(87_800)));
+        assert!(!plan.contains(Frequency::from_khz(108_000)));
+        assert!(!plan.contains(Frequency::from_khz(76_000)));
+    }
+
+    #[test]
+    fn can_iterate_channels() {
+        let plan = plan(Band::Mhz76_90, ChannelSpacing::Khz200);
+        let mut channels = plan.iter();
+        assert_eq!(71, channels.len());
+        assert_eq!(Some(Frequency::from_khz(76_000)), channels.next());
+        assert_eq!(Some(Frequency::from_khz(90_000)), channels.next_back());
+        assert_eq!(69, channels.len());
+        assert_eq!(71, plan.into_iter().count());
+        assert!(plan.iter().all(|f| plan.contains(f)));
+    }
+
+    #[test]
+    fn can_create_from_region() {
+        let plan = ChannelPlan::from(Region::Japan);
+        assert_eq!(Band::Mhz76_90, plan.band);
+        assert_eq!(ChannelSpacing::Khz100, plan.spacing);
+    }
+}
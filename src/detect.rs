@@ -0,0 +1,72 @@
+use crate::{
+    fields,
+    hal::{Eh1, I2cBus},
+    ic, DeviceKind, Error, Si4703,
+};
+use embedded_hal::i2c::I2c;
+
+/// Driver for a device detected at runtime
+///
+/// See [`detect()`](fn.detect.html).
+#[derive(Debug)]
+pub enum DetectedDevice<I2C, HAL = Eh1> {
+    /// Si4700 device, without RDS support
+    Si4700(Si4703<I2C, ic::Si4700, HAL>),
+    /// Si4701 device, with RDS support
+    Si4701(Si4703<I2C, ic::Si4701, HAL>),
+    /// Si4702 device, without RDS support
+    Si4702(Si4703<I2C, ic::Si4702, HAL>),
+    /// Si4703 device, with RDS support
+    Si4703(Si4703<I2C, ic::Si4703, HAL>),
+}
+
+impl<I2C, HAL> DetectedDevice<I2C, HAL> {
+    /// Destroy driver instance, return I²C bus instance.
+    pub fn destroy(self) -> I2C {
+        match self {
+            DetectedDevice::Si4700(dev) => dev.destroy(),
+            DetectedDevice::Si4701(dev) => dev.destroy(),
+            DetectedDevice::Si4702(dev) => dev.destroy(),
+            DetectedDevice::Si4703(dev) => dev.destroy(),
+        }
+    }
+}
+
+/// Detect the device and create the appropriate driver
+///
+/// The DEVICEID and CHIPID registers are read and the driver is selected
+/// depending on the reported device.
+/// Before powerup, the Si4702/03 report the same device as the Si4700/01
+/// and no firmware version. In that case, only the RDS support can be told
+/// apart and the Si4702 or Si4703 driver is selected. For example, an
+/// Si4701 that has not been powered up yet reads DEV=1000 and firmware 0
+/// like an Si4703 and is detected as an Si4703.
+/// [`init()`](struct.Si4703.html#method.init) then fails with
+/// `InitError::UnexpectedDevice` containing the Si4701 identification
+/// read after powerup. Power up the device before calling this to detect
+/// the Si4700/01 reliably.
+///
+/// Returns `Error::UnsupportedDevice` if the part number, manufacturer
+/// or device are unknown.
+pub fn detect<I2C, E>(i2c: I2C) -> Result<DetectedDevice<I2C>, Error<E>>
+where
+    I2C: I2c<Error = E>,
+{
+    detect_device(Si4703::new(i2c))
+}
+
+/// Select the driver for the device reported by the chip.
+pub(crate) fn detect_device<I2C, E, HAL>(
+    mut dev: Si4703<I2C, ic::Si4703, HAL>,
+) -> Result<DetectedDevice<I2C, HAL>, Error<E>>
+where
+    I2C: I2cBus<HAL, Error = E>,
+{
+    let info = fields::chip_info(&dev.read_all_registers()?);
+    match fields::detected_device(&info)? {
+        DeviceKind::Si4700 => Ok(DetectedDevice::Si4700(dev.into_ic())),
+        DeviceKind::Si4701 => Ok(DetectedDevice::Si4701(dev.into_ic())),
+        DeviceKind::Si4703 => Ok(DetectedDevice::Si4703(dev)),
+        _ => Ok(DetectedDevice::Si4702(dev.into_ic())),
+    }
+}
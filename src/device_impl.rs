@@ -1,61 +1,126 @@
 use crate::{
-    ic, Band, BitFlags, ChannelSpacing, DeEmphasis, Error, Gpio1Config, Gpio2Config, Gpio3Config,
-    OperationState, OutputMode, Register, Si4703, SoftmuteAttenuation, SoftmuteRate,
+    fields,
+    hal::I2cBus,
+    ic, marker, private,
+    registers::{RegisterDump, RegisterValue, WritableRegister},
+    Band, ChannelSpacing, ChipInfo, Config, DeEmphasis, Error, Frequency, Gpio1Config, Gpio2Config,
+    Gpio3Config, Operation, OperationState, OutputMode, Region, Register, ScanState,
+    SeekFmImpulseThreshold, SeekSnrThreshold, Si4703, SoftmuteAttenuation, SoftmuteRate, Status,
     StereoToMonoBlendLevel, Volume,
 };
 use core::marker::PhantomData;
-use embedded_hal::blocking::i2c;
+use embedded_hal::i2c::I2c;
 
 impl<I2C, E> Si4703<I2C, ic::Si4703>
 where
-    I2C: i2c::Write<Error = E> + i2c::Read<Error = E>,
+    I2C: I2c<Error = E>,
 {
     /// Create new instance of a Si4703 device
     pub fn new(i2c: I2C) -> Self {
-        Si4703 {
-            i2c,
-            seeking_state: OperationState::Idle,
-            tuning_state: OperationState::Idle,
-            _ic: PhantomData,
-        }
+        Self::create(i2c)
     }
 }
 
 impl<I2C, E> Si4703<I2C, ic::Si4702>
 where
-    I2C: i2c::Write<Error = E> + i2c::Read<Error = E>,
+    I2C: I2c<Error = E>,
 {
     /// Create new instance of a Si4702 device
     pub fn new_si4702(i2c: I2C) -> Self {
+        Self::create(i2c)
+    }
+}
+
+impl<I2C, E> Si4703<I2C, ic::Si4700>
+where
+    I2C: I2c<Error = E>,
+{
+    /// Create new instance of a Si4700 device
+    pub fn new_si4700(i2c: I2C) -> Self {
+        Self::create(i2c)
+    }
+}
+
+impl<I2C, E> Si4703<I2C, ic::Si4701>
+where
+    I2C: I2c<Error = E>,
+{
+    /// Create new instance of a Si4701 device
+    pub fn new_si4701(i2c: I2C) -> Self {
+        Self::create(i2c)
+    }
+}
+
+impl<I2C, IC, HAL> Si4703<I2C, IC, HAL> {
+    /// Create a driver instance in its initial state
+    pub(crate) fn create(i2c: I2C) -> Self {
         Si4703 {
             i2c,
-            seeking_state: OperationState::Idle,
-            tuning_state: OperationState::Idle,
+            shadow: [0; 16],
+            shadow_valid: false,
+            operation_state: OperationState::Idle,
+            operation_polls: 0,
+            scan_state: ScanState::Idle,
+            power_down_state: None,
             _ic: PhantomData,
+            _hal: PhantomData,
         }
     }
-}
 
-impl<I2C, IC> Si4703<I2C, IC> {
+    /// Change the IC marker keeping the driver state
+    pub(crate) fn into_ic<IC2>(self) -> Si4703<I2C, IC2, HAL> {
+        Si4703 {
+            i2c: self.i2c,
+            shadow: self.shadow,
+            shadow_valid: self.shadow_valid,
+            operation_state: self.operation_state,
+            operation_polls: self.operation_polls,
+            scan_state: self.scan_state,
+            power_down_state: self.power_down_state,
+            _ic: PhantomData,
+            _hal: PhantomData,
+        }
+    }
+
+    /// Whether a seek or tune operation started by the driver is in progress
+    pub fn is_busy(&self) -> bool {
+        self.operation_state != OperationState::Idle
+    }
+
+    /// Seek or tune operation currently in progress, if any
+    ///
+    /// Only one operation can be in progress at a time. Starting another
+    /// one returns `Error::Busy` until the current one has finished or
+    /// has been cancelled.
+    pub fn current_operation(&self) -> Option<Operation> {
+        self.operation_state.operation()
+    }
+
     /// Destroy driver instance, return I²C bus instance.
     pub fn destroy(self) -> I2C {
         self.i2c
     }
 }
 
-impl<I2C, E, IC> Si4703<I2C, IC>
+impl<I2C, E, IC, HAL> Si4703<I2C, IC, HAL>
 where
-    I2C: i2c::Write<Error = E> + i2c::Read<Error = E>,
+    I2C: I2cBus<HAL, Error = E>,
 {
+    /// Refresh the shadow copy of the control registers from the device.
+    ///
+    /// This must be called if the device has been reset or the registers
+    /// have been changed by other means since this driver last accessed them.
+    pub fn resync(&mut self) -> Result<(), Error<E>> {
+        self.read_all_registers().map(|_| ())
+    }
+
     /// Enable the oscillator.
     ///
     /// This must be called before enabling the device.
     /// After calling this, a minimum of 500ms must be waited in order
     /// for the oscillator to power up.
     pub fn enable_oscillator(&mut self) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        regs[Register::TEST1] |= BitFlags::XOSCEN;
-        self.write_registers(&regs[0..=Register::TEST1])
+        self.update_registers(|regs| fields::set_oscillator(regs, true))
     }
 
     /// Enable the device.
@@ -64,26 +129,22 @@ where
     /// See: Powerup time in the datasheet.
     /// On the Si4703, this is a maximum of 110ms.
     pub fn enable(&mut self) -> Result<(), Error<E>> {
-        let powercfg = self.read_powercfg()?;
-        self.write_powercfg((powercfg | BitFlags::ENABLE) & !BitFlags::DISABLE)
+        self.update_registers(|regs| fields::set_enable(regs, true))
     }
 
     /// Disable the device (power down).
     pub fn disable(&mut self) -> Result<(), Error<E>> {
-        let powercfg = self.read_powercfg()?;
-        self.write_powercfg(powercfg | BitFlags::ENABLE | BitFlags::DISABLE)
+        self.update_registers(|regs| fields::set_enable(regs, false))
     }
 
     /// Unmute (disable mute)
     pub fn unmute(&mut self) -> Result<(), Error<E>> {
-        let powercfg = self.read_powercfg()?;
-        self.write_powercfg(powercfg | BitFlags::DMUTE)
+        self.update_registers(|regs| fields::set_mute(regs, false))
     }
 
     /// Mute (enable mute)
     pub fn mute(&mut self) -> Result<(), Error<E>> {
-        let powercfg = self.read_powercfg()?;
-        self.write_powercfg(powercfg & !BitFlags::DMUTE)
+        self.update_registers(|regs| fields::set_mute(regs, true))
     }
 
     /// Enable softmute
@@ -92,139 +153,80 @@ where
         rate: SoftmuteRate,
         attenuation: SoftmuteAttenuation,
     ) -> Result<(), Error<E>> {
-        let rate_mask = match rate {
-            SoftmuteRate::Fastest => 0,
-            SoftmuteRate::Fast => 1,
-            SoftmuteRate::Slow => 2,
-            SoftmuteRate::Slowest => 3,
-        };
-        let attenuation_mask = match attenuation {
-            SoftmuteAttenuation::Db16 => 0,
-            SoftmuteAttenuation::Db14 => 1,
-            SoftmuteAttenuation::Db12 => 2,
-            SoftmuteAttenuation::Db10 => 3,
-        };
-
-        let mut regs = self.read_registers()?;
-        regs[Register::POWERCFG] &= !BitFlags::DSMUTE;
-        regs[Register::SYSCONFIG3] &= 0x0FFF;
-        regs[Register::SYSCONFIG3] |= (rate_mask << 14) | (attenuation_mask << 12);
-        self.write_registers(&regs[0..=Register::SYSCONFIG3])
+        self.update_registers(|regs| fields::enable_softmute(regs, rate, attenuation))
     }
 
     /// Disable softmute
     pub fn disable_softmute(&mut self) -> Result<(), Error<E>> {
-        let powercfg = self.read_powercfg()?;
-        self.write_powercfg(powercfg | BitFlags::DSMUTE)
+        self.update_registers(fields::disable_softmute)
     }
 
     /// Get output mode: Stereo/Mono
     pub fn output_mode(&mut self) -> Result<OutputMode, Error<E>> {
         let status = self.read_status()?;
-        if (status & BitFlags::ST) != 0 {
-            Ok(OutputMode::Stereo)
-        } else {
-            Ok(OutputMode::Mono)
-        }
+        Ok(fields::output_mode(status))
+    }
+
+    /// Get the received signal strength indicator (RSSI) in dBµV
+    pub fn rssi(&mut self) -> Result<u8, Error<E>> {
+        let status = self.read_status()?;
+        Ok(fields::rssi(status))
+    }
+
+    /// Get the output mode (Stereo/Mono) and the RSSI in dBµV
+    /// with a single read.
+    pub fn output_mode_and_rssi(&mut self) -> Result<(OutputMode, u8), Error<E>> {
+        let status = self.read_status()?;
+        Ok((fields::output_mode(status), fields::rssi(status)))
+    }
+
+    /// Get the device status
+    ///
+    /// The STATUSRSSI and READCHAN registers are read in a single transaction.
+    pub fn status(&mut self) -> Result<Status, Error<E>> {
+        let regs = self.read_some_registers(2)?;
+        Ok(fields::status(
+            regs[Register::STATUSRSSI],
+            regs[Register::READCHAN],
+        ))
     }
 
     /// Set output mode: Stereo/Mono
     pub fn set_output_mode(&mut self, mode: OutputMode) -> Result<(), Error<E>> {
-        let powercfg = self.read_powercfg()?;
-        let powercfg = match mode {
-            OutputMode::Stereo => powercfg & !BitFlags::MONO,
-            OutputMode::Mono => powercfg | BitFlags::MONO,
-        };
-        self.write_powercfg(powercfg)
+        self.update_registers(|regs| fields::set_output_mode(regs, mode))
+    }
+
+    /// Set the band, channel spacing and de-emphasis used in a region
+    pub fn set_region(&mut self, region: Region) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_region(regs, region))
     }
 
     /// Set de-emphasis
     pub fn set_deemphasis(&mut self, de: DeEmphasis) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        match de {
-            DeEmphasis::Us75 => regs[Register::SYSCONFIG1] &= !BitFlags::DE,
-            DeEmphasis::Us50 => regs[Register::SYSCONFIG1] |= BitFlags::DE,
-        }
-        self.write_registers(&regs[0..=Register::SYSCONFIG1])
+        self.update_registers(|regs| fields::set_deemphasis(regs, de))
     }
 
     /// Set the volume
     ///
     /// This will automatically activate or deactivate the extended volume
-    /// range as appropriate.
-    pub fn set_volume(&mut self, volume: Volume) -> Result<(), Error<E>> {
-        let (volume_mask, volext) = match volume {
-            Volume::Mute => (0_u16, false),
-            Volume::Dbfsm58 => (1, true),
-            Volume::Dbfsm56 => (2, true),
-            Volume::Dbfsm54 => (3, true),
-            Volume::Dbfsm52 => (4, true),
-            Volume::Dbfsm50 => (5, true),
-            Volume::Dbfsm48 => (6, true),
-            Volume::Dbfsm46 => (7, true),
-            Volume::Dbfsm44 => (8, true),
-            Volume::Dbfsm42 => (9, true),
-            Volume::Dbfsm40 => (10, true),
-            Volume::Dbfsm38 => (11, true),
-            Volume::Dbfsm36 => (12, true),
-            Volume::Dbfsm34 => (13, true),
-            Volume::Dbfsm32 => (14, true),
-            Volume::Dbfsm30 => (15, true),
-            Volume::Dbfsm28 => (1, false),
-            Volume::Dbfsm26 => (2, false),
-            Volume::Dbfsm24 => (3, false),
-            Volume::Dbfsm22 => (4, false),
-            Volume::Dbfsm20 => (5, false),
-            Volume::Dbfsm18 => (6, false),
-            Volume::Dbfsm16 => (7, false),
-            Volume::Dbfsm14 => (8, false),
-            Volume::Dbfsm12 => (9, false),
-            Volume::Dbfsm10 => (10, false),
-            Volume::Dbfsm8 => (11, false),
-            Volume::Dbfsm6 => (12, false),
-            Volume::Dbfsm4 => (13, false),
-            Volume::Dbfsm2 => (14, false),
-            Volume::Dbfs0 => (15, false),
-        };
-        let mut regs = self.read_registers()?;
-        regs[Register::SYSCONFIG2] &= 0xFFF0;
-        regs[Register::SYSCONFIG2] |= volume_mask;
-        if volume_mask == 0 {
-            self.write_registers(&regs[0..=Register::SYSCONFIG2])
-        } else {
-            if volext {
-                regs[Register::SYSCONFIG3] |= BitFlags::VOLEXT
-            } else {
-                regs[Register::SYSCONFIG3] &= !BitFlags::VOLEXT
-            }
-            self.write_registers(&regs[0..=Register::SYSCONFIG3])
-        }
+    /// range as appropriate. The extended volume range is only available on
+    /// the Si4702/03, otherwise `Error::InvalidInputData` is returned.
+    pub fn set_volume(&mut self, volume: Volume) -> Result<(), Error<E>>
+    where
+        IC: private::ExpectedDevice,
+    {
+        fields::check_volume(IC::DEVICE, volume)?;
+        self.update_registers(|regs| fields::set_volume(regs, volume))
     }
 
     /// Set band
     pub fn set_band(&mut self, band: Band) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        let mask = match band {
-            Band::Mhz875_108 => 0,
-            Band::Mhz76_108 => 1,
-            Band::Mhz76_90 => 2,
-        };
-        regs[Register::SYSCONFIG2] &= !(0b11 << 6);
-        regs[Register::SYSCONFIG2] |= mask << 6;
-        self.write_registers(&regs[..=Register::SYSCONFIG2])
+        self.update_registers(|regs| fields::set_band(regs, band))
     }
 
     /// Set channel spacing
     pub fn set_channel_spacing(&mut self, spacing: ChannelSpacing) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        let mask = match spacing {
-            ChannelSpacing::Khz200 => 0,
-            ChannelSpacing::Khz100 => 1,
-            ChannelSpacing::Khz50 => 2,
-        };
-        regs[Register::SYSCONFIG2] &= !(0b11 << 4);
-        regs[Register::SYSCONFIG2] |= mask << 4;
-        self.write_registers(&regs[..=Register::SYSCONFIG2])
+        self.update_registers(|regs| fields::set_channel_spacing(regs, spacing))
     }
 
     /// Enable generating STC interrupts.
@@ -232,71 +234,37 @@ where
     /// For this to be useful, gpio2 pin must be configured to be
     /// used as STC/RDS interrupt.
     pub fn enable_stc_interrupts(&mut self) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        regs[Register::SYSCONFIG1] |= BitFlags::STCIEN;
-        self.write_registers(&regs[0..=Register::SYSCONFIG1])
+        self.update_registers(|regs| fields::set_stc_interrupts(regs, true))
     }
 
     /// Disable generating STC interrupts.
     pub fn disable_stc_interrupts(&mut self) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        regs[Register::SYSCONFIG1] &= !BitFlags::STCIEN;
-        self.write_registers(&regs[0..=Register::SYSCONFIG1])
+        self.update_registers(|regs| fields::set_stc_interrupts(regs, false))
     }
 
     /// Set the GPIO1
     pub fn set_gpio1(&mut self, config: Gpio1Config) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        let mask = match config {
-            Gpio1Config::HighImpedance => 0,
-            Gpio1Config::Low => 2,
-            Gpio1Config::High => 3,
-        };
-        regs[Register::SYSCONFIG1] &= 0xFFFC;
-        regs[Register::SYSCONFIG1] |= mask;
-        self.write_registers(&regs[0..=Register::SYSCONFIG1])
+        self.update_registers(|regs| fields::set_gpio1(regs, config))
     }
 
     /// Set GPIO2 function / status
     pub fn set_gpio2(&mut self, config: Gpio2Config) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        let mask = match config {
-            Gpio2Config::HighImpedance => 0,
-            Gpio2Config::StcRdsInterrupt => 1,
-            Gpio2Config::Low => 2,
-            Gpio2Config::High => 3,
-        };
-        regs[Register::SYSCONFIG1] &= 0xFFF3;
-        regs[Register::SYSCONFIG1] |= mask << 2;
-        self.write_registers(&regs[0..=Register::SYSCONFIG1])
+        self.update_registers(|regs| fields::set_gpio2(regs, config))
     }
 
     /// Set the GPIO3
     pub fn set_gpio3(&mut self, config: Gpio3Config) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        let mask = match config {
-            Gpio3Config::HighImpedance => 0,
-            Gpio3Config::MonoStereoIndicator => 1,
-            Gpio3Config::Low => 2,
-            Gpio3Config::High => 3,
-        };
-        regs[Register::SYSCONFIG1] &= 0xFFCF;
-        regs[Register::SYSCONFIG1] |= mask << 4;
-        self.write_registers(&regs[0..=Register::SYSCONFIG1])
+        self.update_registers(|regs| fields::set_gpio3(regs, config))
     }
 
     /// Enable automatic gain control (default).
     pub fn enable_auto_gain_control(&mut self) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        regs[Register::SYSCONFIG1] &= !BitFlags::AGCD;
-        self.write_registers(&regs[0..=Register::SYSCONFIG1])
+        self.update_registers(|regs| fields::set_auto_gain_control(regs, true))
     }
 
     /// Disable automatic gain control.
     pub fn disable_auto_gain_control(&mut self) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        regs[Register::SYSCONFIG1] |= BitFlags::AGCD;
-        self.write_registers(&regs[0..=Register::SYSCONFIG1])
+        self.update_registers(|regs| fields::set_auto_gain_control(regs, false))
     }
 
     /// Set the stereo to mono blend level
@@ -304,71 +272,224 @@ where
         &mut self,
         level: StereoToMonoBlendLevel,
     ) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        let mask = match level {
-            StereoToMonoBlendLevel::Dbuv31_49 => 0,
-            StereoToMonoBlendLevel::Dbuv37_55 => 1,
-            StereoToMonoBlendLevel::Dbuv19_37 => 2,
-            StereoToMonoBlendLevel::Dbuv25_43 => 3,
-        };
-        regs[Register::SYSCONFIG1] &= 0xFF3F;
-        regs[Register::SYSCONFIG1] |= mask << 6;
-        self.write_registers(&regs[0..=Register::SYSCONFIG1])
+        self.update_registers(|regs| fields::set_stereo_to_mono_blend_level(regs, level))
     }
 
-    /// Enable the audio High-Z.
+    /// Get whether the oscillator is enabled.
+    pub fn is_oscillator_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_oscillator_enabled(&self.cached_registers()?))
+    }
+
+    /// Get whether the device is enabled (powered up).
+    pub fn is_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_enabled(&self.cached_registers()?))
+    }
+
+    /// Get whether the audio is muted.
+    pub fn is_muted(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_muted(&self.cached_registers()?))
+    }
+
+    /// Get the softmute rate and attenuation
     ///
-    /// This must be called before enabling the device.
-    pub fn enable_audio_high_z(&mut self) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        regs[Register::TEST1] |= BitFlags::AHIZEN;
-        self.write_registers(&regs[0..=Register::TEST1])
+    /// Returns `None` if softmute is disabled.
+    pub fn softmute(&mut self) -> Result<Option<(SoftmuteRate, SoftmuteAttenuation)>, Error<E>> {
+        Ok(fields::softmute(&self.cached_registers()?))
     }
 
-    /// Disable the audio High-Z.
+    /// Get the configured output mode: Stereo/Mono
     ///
-    /// This must be called before enabling the device.
-    pub fn disable_audio_high_z(&mut self) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        regs[Register::TEST1] &= !BitFlags::AHIZEN;
-        self.write_registers(&regs[0..=Register::TEST1])
+    /// This is `Mono` if mono output has been forced. See `output_mode()`
+    /// for the mode actually being received.
+    pub fn configured_output_mode(&mut self) -> Result<OutputMode, Error<E>> {
+        Ok(fields::configured_output_mode(&self.cached_registers()?))
+    }
+
+    /// Get the region matching the configured band, channel spacing
+    /// and de-emphasis, if any
+    pub fn region(&mut self) -> Result<Option<Region>, Error<E>> {
+        Ok(fields::region(&self.cached_registers()?))
+    }
+
+    /// Get the de-emphasis
+    pub fn deemphasis(&mut self) -> Result<DeEmphasis, Error<E>> {
+        Ok(fields::deemphasis(&self.cached_registers()?))
+    }
+
+    /// Get the volume
+    pub fn volume(&mut self) -> Result<Volume, Error<E>> {
+        Ok(fields::volume(&self.cached_registers()?))
+    }
+
+    /// Get the band
+    pub fn band(&mut self) -> Result<Band, Error<E>> {
+        Ok(fields::band(&self.cached_registers()?))
+    }
+
+    /// Get the channel spacing
+    pub fn channel_spacing(&mut self) -> Result<ChannelSpacing, Error<E>> {
+        Ok(fields::channel_spacing(&self.cached_registers()?))
+    }
+
+    /// Get whether generating STC interrupts is enabled.
+    pub fn is_stc_interrupts_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_stc_interrupts_enabled(&self.cached_registers()?))
+    }
+
+    /// Get the GPIO1 configuration
+    pub fn gpio1(&mut self) -> Result<Gpio1Config, Error<E>> {
+        Ok(fields::gpio1(&self.cached_registers()?))
+    }
+
+    /// Get the GPIO2 configuration
+    pub fn gpio2(&mut self) -> Result<Gpio2Config, Error<E>> {
+        Ok(fields::gpio2(&self.cached_registers()?))
+    }
+
+    /// Get the GPIO3 configuration
+    pub fn gpio3(&mut self) -> Result<Gpio3Config, Error<E>> {
+        Ok(fields::gpio3(&self.cached_registers()?))
+    }
+
+    /// Get whether automatic gain control is enabled.
+    pub fn is_auto_gain_control_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_auto_gain_control_enabled(
+            &self.cached_registers()?,
+        ))
+    }
+
+    /// Get the stereo to mono blend level
+    pub fn blend_level(&mut self) -> Result<StereoToMonoBlendLevel, Error<E>> {
+        Ok(fields::blend_level(&self.cached_registers()?))
+    }
+
+    /// Get the seek RSSI, SNR and FM impulse detection thresholds
+    ///
+    /// Returns the (RSSI, SNR, FM impulse) thresholds as a tuple
+    pub fn seek_config(
+        &mut self,
+    ) -> Result<(u8, SeekSnrThreshold, SeekFmImpulseThreshold), Error<E>> {
+        Ok(fields::seek_config(&self.cached_registers()?))
+    }
+
+    /// Apply the whole configuration
+    ///
+    /// All control registers (POWERCFG to TEST1) are written in a single
+    /// transaction. Settings not part of the configuration like enable,
+    /// mute or the output mode are kept.
+    pub fn apply_config(&mut self, config: &Config) -> Result<(), Error<E>>
+    where
+        IC: private::ExpectedDevice,
+    {
+        let thresholds = fields::check_config(IC::DEVICE, config)?;
+        self.update_registers(|regs| fields::apply_config(regs, config, thresholds))
+    }
+
+    /// Read the whole configuration from the device
+    ///
+    /// This also refreshes the shadow copy of the control registers.
+    pub fn read_config(&mut self) -> Result<Config, Error<E>> {
+        let regs = self.read_all_registers()?;
+        Ok(fields::config(&regs))
     }
 
     /// Read the channel
     pub fn channel(&mut self) -> Result<f32, Error<E>> {
-        let regs = self.read_registers()?;
-        let spacing = (regs[Register::SYSCONFIG2] & (0b11 << 4)) >> 4;
-        let spacing = match spacing {
-            0 => 0.2,
-            1 => 0.1,
-            _ => 0.05,
-        };
-        let base = regs[Register::SYSCONFIG2] & (0b11 << 6);
-        let base = if base == 0 { 87.5 } else { 76.0 };
-        let channel = f32::from(regs[Register::READCHAN] & 0x3FF);
-        Ok(channel * spacing + base)
+        let sysconfig2 = self.cached_registers()?[Register::SYSCONFIG2];
+        let readchan = self.read_some_registers(2)?[Register::READCHAN];
+        Ok(fields::channel_mhz(sysconfig2, readchan))
+    }
+
+    /// Read the channel frequency
+    ///
+    /// Unlike [`channel()`](#method.channel), this does not use floating
+    /// point operations.
+    pub fn channel_khz(&mut self) -> Result<Frequency, Error<E>> {
+        let sysconfig2 = self.cached_registers()?[Register::SYSCONFIG2];
+        let readchan = self.read_some_registers(2)?[Register::READCHAN];
+        Ok(fields::channel_khz(sysconfig2, readchan))
     }
 
     /// Get the device ID
     ///
     /// Returns the (part number, manufacturer ID) as a tuple
     pub fn device_id(&mut self) -> Result<(u8, u16), Error<E>> {
-        let regs = self.read_registers()?;
-        let device_id = regs[Register::DEVICE_ID];
-        let pn = ((device_id & 0xF000) >> 12) as u8;
-        let mfid = device_id & 0xFFF;
-        Ok((pn, mfid))
+        let regs = self.read_all_registers()?;
+        Ok(fields::device_id(regs[Register::DEVICE_ID]))
     }
 
     /// Get the chip ID
     ///
     /// Returns the (revision, device, firmware) as a tuple
     pub fn chip_id(&mut self) -> Result<(u8, u8, u8), Error<E>> {
-        let regs = self.read_registers()?;
-        let chip_id = regs[Register::CHIP_ID];
-        let rev = ((chip_id & 0xFC00) >> 10) as u8;
-        let dev = ((chip_id & 0x3C0) >> 6) as u8;
-        let firmware = (chip_id & 0x3F) as u8;
-        Ok((rev, dev, firmware))
+        let regs = self.read_all_registers()?;
+        Ok(fields::chip_id(regs[Register::CHIP_ID]))
+    }
+
+    /// Get the chip identification
+    ///
+    /// The Si4702/03 only report their device and firmware after powerup.
+    pub fn chip_info(&mut self) -> Result<ChipInfo, Error<E>> {
+        let regs = self.read_all_registers()?;
+        Ok(fields::chip_info(&regs))
+    }
+
+    /// Read all the registers
+    ///
+    /// The returned [`RegisterDump`](registers/struct.RegisterDump.html) can be printed to
+    /// show every field of every register, which is useful for diagnostics.
+    pub fn read_registers(&mut self) -> Result<RegisterDump, Error<E>> {
+        Ok(RegisterDump::from(self.read_all_registers()?))
+    }
+
+    /// Read a register
+    ///
+    /// This gives access to every field of the register, including those
+    /// not otherwise supported by this driver.
+    /// See the [`registers`](registers/index.html) module.
+    pub fn read_register<R: RegisterValue>(&mut self) -> Result<R, Error<E>> {
+        let regs = if R::ADDRESS >= Register::STATUSRSSI {
+            self.read_some_registers(R::ADDRESS - Register::STATUSRSSI + 1)?
+        } else {
+            self.read_all_registers()?
+        };
+        Ok(R::from(regs[R::ADDRESS]))
+    }
+
+    /// Write a register
+    ///
+    /// The registers from POWERCFG up to the given one are written.
+    /// The values for the registers before it are taken from the cache.
+    /// See the [`registers`](registers/index.html) module.
+    pub fn write_register<R: WritableRegister>(&mut self, value: R) -> Result<(), Error<E>> {
+        self.update_registers(|regs| {
+            regs[R::ADDRESS] = value.into();
+            R::ADDRESS
+        })
+    }
+}
+
+impl<I2C, E, IC, HAL> Si4703<I2C, IC, HAL>
+where
+    I2C: I2cBus<HAL, Error = E>,
+    IC: marker::WithAudioHighZ,
+{
+    /// Enable the audio High-Z.
+    ///
+    /// This must be called before enabling the device.
+    pub fn enable_audio_high_z(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_audio_high_z(regs, true))
+    }
+
+    /// Disable the audio High-Z.
+    ///
+    /// This must be called before enabling the device.
+    pub fn disable_audio_high_z(&mut self) -> Result<(), Error<E>> {
+        self.update_registers(|regs| fields::set_audio_high_z(regs, false))
+    }
+
+    /// Get whether the audio High-Z is enabled.
+    pub fn is_audio_high_z_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_audio_high_z_enabled(&self.cached_registers()?))
     }
 }
@@ -0,0 +1,134 @@
+//! Support for `embedded-hal` 0.2 (`eh02` feature)
+//!
+//! Drivers created with the constructors in this module, for example
+//! [`Si4703::new_eh02()`](../struct.Si4703.html#method.new_eh02), take
+//! an I²C bus implementing the `embedded-hal` 0.2 `blocking::i2c::Read`
+//! and `blocking::i2c::Write` traits. Their methods take input pins
+//! implementing `digital::v2::InputPin`, output pins implementing
+//! `digital::v2::OutputPin` and delays implementing
+//! `blocking::delay::DelayMs<u32>` directly.
+//!
+//! ```no_run
+//! use linux_embedded_hal::{Delay, I2cdev, Pin};
+//! use si4703::{eh02::reset_and_select_i2c_method1, Si4703};
+//!
+//! let mut delay = Delay {};
+//! let mut sda = Pin::new(2);
+//! let mut rst = Pin::new(17);
+//! reset_and_select_i2c_method1(&mut rst, &mut sda, &mut delay).unwrap();
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut radio = Si4703::new_eh02(dev);
+//! radio.enable_oscillator().unwrap();
+//! ```
+
+use crate::{
+    detect::detect_device,
+    hal::{Eh02, I2cBus},
+    ic, reset, DetectedDevice, Error, Si4703,
+};
+use embedded_hal_0_2::{blocking::delay::DelayMs, digital::v2::OutputPin};
+
+impl<I2C, E> Si4703<I2C, ic::Si4703, Eh02>
+where
+    I2C: I2cBus<Eh02, Error = E>,
+{
+    /// Create new instance of a Si4703 device using `embedded-hal` 0.2
+    pub fn new_eh02(i2c: I2C) -> Self {
+        Self::create(i2c)
+    }
+}
+
+impl<I2C, E> Si4703<I2C, ic::Si4702, Eh02>
+where
+    I2C: I2cBus<Eh02, Error = E>,
+{
+    /// Create new instance of a Si4702 device using `embedded-hal` 0.2
+    pub fn new_si4702_eh02(i2c: I2C) -> Self {
+        Self::create(i2c)
+    }
+}
+
+impl<I2C, E> Si4703<I2C, ic::Si4700, Eh02>
+where
+    I2C: I2cBus<Eh02, Error = E>,
+{
+    /// Create new instance of a Si4700 device using `embedded-hal` 0.2
+    pub fn new_si4700_eh02(i2c: I2C) -> Self {
+        Self::create(i2c)
+    }
+}
+
+impl<I2C, E> Si4703<I2C, ic::Si4701, Eh02>
+where
+    I2C: I2cBus<Eh02, Error = E>,
+{
+    /// Create new instance of a Si4701 device using `embedded-hal` 0.2
+    pub fn new_si4701_eh02(i2c: I2C) -> Self {
+        Self::create(i2c)
+    }
+}
+
+/// Detect the device and create the appropriate driver using `embedded-hal` 0.2
+///
+/// See [`detect()`](../fn.detect.html).
+pub fn detect<I2C, E>(i2c: I2C) -> Result<DetectedDevice<I2C, Eh02>, Error<E>>
+where
+    I2C: I2cBus<Eh02, Error = E>,
+{
+    detect_device(Si4703::new_eh02(i2c))
+}
+
+/// Reset the device and select I2C communication (method 1, no GPIO3)
+/// using `embedded-hal` 0.2
+///
+/// See [`reset_and_select_i2c_method1()`](../fn.reset_and_select_i2c_method1.html).
+pub fn reset_and_select_i2c_method1<
+    E,
+    RST: OutputPin<Error = E>,
+    SDA: OutputPin<Error = E>,
+    DELAY: DelayMs<u32>,
+>(
+    rst: &mut RST,
+    sda: &mut SDA,
+    delay: &mut DELAY,
+) -> Result<(), E> {
+    reset::method1::<Eh02, _, _, _, _>(rst, sda, delay)
+}
+
+/// Reset the device and select I2C communication (method 1 including GPIO3)
+/// using `embedded-hal` 0.2
+///
+/// See [`reset_and_select_i2c_method1_with_gpio3()`](../fn.reset_and_select_i2c_method1_with_gpio3.html).
+pub fn reset_and_select_i2c_method1_with_gpio3<
+    E,
+    RST: OutputPin<Error = E>,
+    SDA: OutputPin<Error = E>,
+    GPIO3: OutputPin<Error = E>,
+    DELAY: DelayMs<u32>,
+>(
+    rst: &mut RST,
+    sda: &mut SDA,
+    gpio3: &mut GPIO3,
+    delay: &mut DELAY,
+) -> Result<(), E> {
+    reset::method1_with_gpio3::<Eh02, _, _, _, _, _>(rst, sda, gpio3, delay)
+}
+
+/// Reset the device and select I2C communication (method 2) using
+/// `embedded-hal` 0.2
+///
+/// See [`reset_and_select_i2c_method2()`](../fn.reset_and_select_i2c_method2.html).
+pub fn reset_and_select_i2c_method2<
+    E,
+    RST: OutputPin<Error = E>,
+    GPIO1: OutputPin<Error = E>,
+    GPIO3: OutputPin<Error = E>,
+    DELAY: DelayMs<u32>,
+>(
+    rst: &mut RST,
+    gpio1: &mut GPIO1,
+    gpio3: &mut GPIO3,
+    delay: &mut DELAY,
+) -> Result<(), E> {
+    reset::method2::<Eh02, _, _, _, _, _>(rst, gpio1, gpio3, delay)
+}
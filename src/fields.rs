@@ -0,0 +1,774 @@
+//! Encoding of the settings into the register values.
+//!
+//! These functions are shared by the blocking and async drivers.
+//! Each of them modifies the register image and returns the index of the
+//! last register that needs to be written.
+
+use crate::{
+    rds::get_block_errors, Band, BitFlags, ChannelPlan, ChannelSpacing, ChipInfo, Config,
+    DeEmphasis, DeviceKind, Error, Frequency, Gpio1Config, Gpio2Config, Gpio3Config, Manufacturer,
+    Operation, OutputMode, PartNumber, PowerDownState, RdsMode, Region, Register, Revision,
+    SeekDirection, SeekFmImpulseThreshold, SeekMode, SeekOutcome, SeekSnrThreshold,
+    SoftmuteAttenuation, SoftmuteRate, Status, StereoToMonoBlendLevel, TuneOutcome, Volume,
+};
+
+pub(crate) type Registers = [u16; 16];
+
+/// Oscillator stabilization time after enabling it
+pub(crate) const OSCILLATOR_STARTUP_MS: u32 = 500;
+/// Powerup time after enabling the device
+pub(crate) const POWERUP_MS: u32 = 110;
+/// STC bit polling interval when no STC interrupt pin is used
+pub(crate) const STC_POLL_INTERVAL_MS: u32 = 10;
+/// Maximum time to wait for the STC bit to clear when cancelling an operation
+pub(crate) const STC_CLEAR_TIMEOUT_MS: u32 = 100;
+/// Maximum time to wait for a tuning to finish when no timeout is given
+pub(crate) const TUNE_TIMEOUT_MS: u32 = 200;
+/// Number of polls of an ongoing operation after which the SEEK/TUNE bit
+/// is read back from the device instead of using the cached value
+pub(crate) const OPERATION_CHECK_POLLS: u8 = 10;
+
+pub(crate) fn set_oscillator(regs: &mut Registers, enable: bool) -> usize {
+    set_flag(regs, Register::TEST1, BitFlags::XOSCEN, enable)
+}
+
+pub(crate) fn set_audio_high_z(regs: &mut Registers, enable: bool) -> usize {
+    set_flag(regs, Register::TEST1, BitFlags::AHIZEN, enable)
+}
+
+pub(crate) fn set_enable(regs: &mut Registers, enable: bool) -> usize {
+    regs[Register::POWERCFG] |= BitFlags::ENABLE;
+    set_flag(regs, Register::POWERCFG, BitFlags::DISABLE, !enable)
+}
+
+/// SYSCONFIG1 bits changed by the power down sequence: RDS and GPIO1-3
+const POWER_DOWN_SYSCONFIG1_MASK: u16 = BitFlags::RDS | 0x3F;
+
+/// Prepare the power down: disable RDS, set the GPIOs to high impedance and
+/// optionally enable the audio high-Z.
+///
+/// Returns the previous settings and the last register to write.
+pub(crate) fn prepare_power_down(
+    regs: &mut Registers,
+    audio_high_z: bool,
+) -> (PowerDownState, usize) {
+    let state = PowerDownState {
+        sysconfig1: regs[Register::SYSCONFIG1] & POWER_DOWN_SYSCONFIG1_MASK,
+        audio_high_z: is_audio_high_z_enabled(regs),
+    };
+    regs[Register::SYSCONFIG1] &= !POWER_DOWN_SYSCONFIG1_MASK;
+    if audio_high_z {
+        (state, set_audio_high_z(regs, true))
+    } else {
+        (state, Register::SYSCONFIG1)
+    }
+}
+
+/// Restore the settings changed by the power down
+pub(crate) fn restore_power_down(regs: &mut Registers, state: &PowerDownState) -> usize {
+    regs[Register::SYSCONFIG1] &= !POWER_DOWN_SYSCONFIG1_MASK;
+    regs[Register::SYSCONFIG1] |= state.sysconfig1;
+    set_audio_high_z(regs, state.audio_high_z)
+}
+
+/// Enable the device restoring the settings changed by the power down, if any
+pub(crate) fn power_up(regs: &mut Registers, state: Option<&PowerDownState>) -> usize {
+    let last = set_enable(regs, true);
+    match state {
+        Some(state) => restore_power_down(regs, state),
+        None => last,
+    }
+}
+
+pub(crate) fn set_mute(regs: &mut Registers, mute: bool) -> usize {
+    set_flag(regs, Register::POWERCFG, BitFlags::DMUTE, !mute)
+}
+
+pub(crate) fn enable_softmute(
+    regs: &mut Registers,
+    rate: SoftmuteRate,
+    attenuation: SoftmuteAttenuation,
+) -> usize {
+    let rate_mask = match rate {
+        SoftmuteRate::Fastest => 0,
+        SoftmuteRate::Fast => 1,
+        SoftmuteRate::Slow => 2,
+        SoftmuteRate::Slowest => 3,
+    };
+    let attenuation_mask = match attenuation {
+        SoftmuteAttenuation::Db16 => 0,
+        SoftmuteAttenuation::Db14 => 1,
+        SoftmuteAttenuation::Db12 => 2,
+        SoftmuteAttenuation::Db10 => 3,
+    };
+    regs[Register::POWERCFG] &= !BitFlags::DSMUTE;
+    regs[Register::SYSCONFIG3] &= 0x0FFF;
+    regs[Register::SYSCONFIG3] |= (rate_mask << 14) | (attenuation_mask << 12);
+    Register::SYSCONFIG3
+}
+
+pub(crate) fn disable_softmute(regs: &mut Registers) -> usize {
+    set_flag(regs, Register::POWERCFG, BitFlags::DSMUTE, true)
+}
+
+pub(crate) fn set_output_mode(regs: &mut Registers, mode: OutputMode) -> usize {
+    set_flag(
+        regs,
+        Register::POWERCFG,
+        BitFlags::MONO,
+        mode == OutputMode::Mono,
+    )
+}
+
+pub(crate) fn set_deemphasis(regs: &mut Registers, de: DeEmphasis) -> usize {
+    set_flag(
+        regs,
+        Register::SYSCONFIG1,
+        BitFlags::DE,
+        de == DeEmphasis::Us50,
+    )
+}
+
+pub(crate) fn set_volume(regs: &mut Registers, volume: Volume) -> usize {
+    let (volume_mask, volext) = match volume {
+        Volume::Mute => (0_u16, false),
+        Volume::Dbfsm58 => (1, true),
+        Volume::Dbfsm56 => (2, true),
+        Volume::Dbfsm54 => (3, true),
+        Volume::Dbfsm52 => (4, true),
+        Volume::Dbfsm50 => (5, true),
+        Volume::Dbfsm48 => (6, true),
+        Volume::Dbfsm46 => (7, true),
+        Volume::Dbfsm44 => (8, true),
+        Volume::Dbfsm42 => (9, true),
+        Volume::Dbfsm40 => (10, true),
+        Volume::Dbfsm38 => (11, true),
+        Volume::Dbfsm36 => (12, true),
+        Volume::Dbfsm34 => (13, true),
+        Volume::Dbfsm32 => (14, true),
+        Volume::Dbfsm30 => (15, true),
+        Volume::Dbfsm28 => (1, false),
+        Volume::Dbfsm26 => (2, false),
+        Volume::Dbfsm24 => (3, false),
+        Volume::Dbfsm22 => (4, false),
+        Volume::Dbfsm20 => (5, false),
+        Volume::Dbfsm18 => (6, false),
+        Volume::Dbfsm16 => (7, false),
+        Volume::Dbfsm14 => (8, false),
+        Volume::Dbfsm12 => (9, false),
+        Volume::Dbfsm10 => (10, false),
+        Volume::Dbfsm8 => (11, false),
+        Volume::Dbfsm6 => (12, false),
+        Volume::Dbfsm4 => (13, false),
+        Volume::Dbfsm2 => (14, false),
+        Volume::Dbfs0 => (15, false),
+    };
+    regs[Register::SYSCONFIG2] &= 0xFFF0;
+    regs[Register::SYSCONFIG2] |= volume_mask;
+    if volume_mask == 0 {
+        Register::SYSCONFIG2
+    } else {
+        set_flag(regs, Register::SYSCONFIG3, BitFlags::VOLEXT, volext)
+    }
+}
+
+/// Check that the device supports the volume.
+///
+/// The extended volume range is only available on the Si4702/03.
+pub(crate) fn check_volume<E>(device: DeviceKind, volume: Volume) -> Result<(), Error<E>> {
+    let extended = matches!(
+        volume,
+        Volume::Dbfsm58
+            | Volume::Dbfsm56
+            | Volume::Dbfsm54
+            | Volume::Dbfsm52
+            | Volume::Dbfsm50
+            | Volume::Dbfsm48
+            | Volume::Dbfsm46
+            | Volume::Dbfsm44
+            | Volume::Dbfsm42
+            | Volume::Dbfsm40
+            | Volume::Dbfsm38
+            | Volume::Dbfsm36
+            | Volume::Dbfsm34
+            | Volume::Dbfsm32
+            | Volume::Dbfsm30
+    );
+    if extended && !device.has_extended_volume() {
+        Err(Error::InvalidInputData)
+    } else {
+        Ok(())
+    }
+}
+
+pub(crate) fn set_band(regs: &mut Registers, band: Band) -> usize {
+    let mask = match band {
+        Band::Mhz875_108 => 0,
+        Band::Mhz76_108 => 1,
+        Band::Mhz76_90 => 2,
+    };
+    regs[Register::SYSCONFIG2] &= !(0b11 << 6);
+    regs[Register::SYSCONFIG2] |= mask << 6;
+    Register::SYSCONFIG2
+}
+
+pub(crate) fn set_channel_spacing(regs: &mut Registers, spacing: ChannelSpacing) -> usize {
+    let mask = match spacing {
+        ChannelSpacing::Khz200 => 0,
+        ChannelSpacing::Khz100 => 1,
+        ChannelSpacing::Khz50 => 2,
+    };
+    regs[Register::SYSCONFIG2] &= !(0b11 << 4);
+    regs[Register::SYSCONFIG2] |= mask << 4;
+    Register::SYSCONFIG2
+}
+
+pub(crate) fn set_region(regs: &mut Registers, region: Region) -> usize {
+    set_deemphasis(regs, region.deemphasis());
+    set_band(regs, region.band());
+    set_channel_spacing(regs, region.channel_spacing())
+}
+
+pub(crate) fn set_stc_interrupts(regs: &mut Registers, enable: bool) -> usize {
+    set_flag(regs, Register::SYSCONFIG1, BitFlags::STCIEN, enable)
+}
+
+pub(crate) fn set_gpio1(regs: &mut Registers, config: Gpio1Config) -> usize {
+    let mask = match config {
+        Gpio1Config::HighImpedance => 0,
+        Gpio1Config::Low => 2,
+        Gpio1Config::High => 3,
+    };
+    regs[Register::SYSCONFIG1] &= 0xFFFC;
+    regs[Register::SYSCONFIG1] |= mask;
+    Register::SYSCONFIG1
+}
+
+pub(crate) fn set_gpio2(regs: &mut Registers, config: Gpio2Config) -> usize {
+    let mask = match config {
+        Gpio2Config::HighImpedance => 0,
+        Gpio2Config::StcRdsInterrupt => 1,
+        Gpio2Config::Low => 2,
+        Gpio2Config::High => 3,
+    };
+    regs[Register::SYSCONFIG1] &= 0xFFF3;
+    regs[Register::SYSCONFIG1] |= mask << 2;
+    Register::SYSCONFIG1
+}
+
+pub(crate) fn set_gpio3(regs: &mut Registers, config: Gpio3Config) -> usize {
+    let mask = match config {
+        Gpio3Config::HighImpedance => 0,
+        Gpio3Config::MonoStereoIndicator => 1,
+        Gpio3Config::Low => 2,
+        Gpio3Config::High => 3,
+    };
+    regs[Register::SYSCONFIG1] &= 0xFFCF;
+    regs[Register::SYSCONFIG1] |= mask << 4;
+    Register::SYSCONFIG1
+}
+
+pub(crate) fn set_auto_gain_control(regs: &mut Registers, enable: bool) -> usize {
+    set_flag(regs, Register::SYSCONFIG1, BitFlags::AGCD, !enable)
+}
+
+pub(crate) fn set_stereo_to_mono_blend_level(
+    regs: &mut Registers,
+    level: StereoToMonoBlendLevel,
+) -> usize {
+    let mask = match level {
+        StereoToMonoBlendLevel::Dbuv31_49 => 0,
+        StereoToMonoBlendLevel::Dbuv37_55 => 1,
+        StereoToMonoBlendLevel::Dbuv19_37 => 2,
+        StereoToMonoBlendLevel::Dbuv25_43 => 3,
+    };
+    regs[Register::SYSCONFIG1] &= 0xFF3F;
+    regs[Register::SYSCONFIG1] |= mask << 6;
+    Register::SYSCONFIG1
+}
+
+/// Validate the seek SNR and FM impulse thresholds and get
+/// the corresponding SYSCONFIG3 bits.
+pub(crate) fn seek_thresholds_mask<E>(
+    snr_threshold: SeekSnrThreshold,
+    fm_impulse_threshold: SeekFmImpulseThreshold,
+) -> Result<u16, Error<E>> {
+    let snr_mask = match snr_threshold {
+        SeekSnrThreshold::Disabled => 0,
+        SeekSnrThreshold::Enabled(v) if v > 7 || v == 0 => return Err(Error::InvalidInputData),
+        SeekSnrThreshold::Enabled(v) => v << 4,
+    };
+    let cnt_mask = match fm_impulse_threshold {
+        SeekFmImpulseThreshold::Disabled => 0,
+        SeekFmImpulseThreshold::Enabled(v) if v > 15 || v == 0 => {
+            return Err(Error::InvalidInputData)
+        }
+        SeekFmImpulseThreshold::Enabled(v) => v,
+    };
+    Ok(u16::from(snr_mask | cnt_mask))
+}
+
+pub(crate) fn configure_seek(regs: &mut Registers, rssi_threshold: u8, thresholds: u16) -> usize {
+    regs[Register::SYSCONFIG2] &= 0x00FF;
+    regs[Register::SYSCONFIG2] |= u16::from(rssi_threshold) << 8;
+    regs[Register::SYSCONFIG3] &= 0xFF00;
+    regs[Register::SYSCONFIG3] |= thresholds;
+    Register::SYSCONFIG3
+}
+
+pub(crate) fn start_seek(regs: &mut Registers, mode: SeekMode, direction: SeekDirection) -> usize {
+    regs[Register::POWERCFG] |= BitFlags::SEEK;
+    set_flag(
+        regs,
+        Register::POWERCFG,
+        BitFlags::SKMODE,
+        mode == SeekMode::Wrap,
+    );
+    set_flag(
+        regs,
+        Register::POWERCFG,
+        BitFlags::SEEKUP,
+        direction == SeekDirection::Up,
+    )
+}
+
+pub(crate) fn start_tune(regs: &mut Registers, raw_channel: u16) -> usize {
+    regs[Register::CHANNEL] = BitFlags::TUNE | raw_channel;
+    Register::CHANNEL
+}
+
+/// Register and bit flag starting an operation.
+pub(crate) fn operation_bit(operation: Operation) -> (usize, u16) {
+    match operation {
+        Operation::Seek => (Register::POWERCFG, BitFlags::SEEK),
+        Operation::Tune => (Register::CHANNEL, BitFlags::TUNE),
+    }
+}
+
+/// Configure GPIO2 as STC interrupt pin and enable STC interrupts.
+///
+/// Returns whether the register value changed.
+pub(crate) fn enable_stc_int_pin(regs: &mut Registers) -> bool {
+    let previous_sysconfig1 = regs[Register::SYSCONFIG1];
+    set_gpio2(regs, Gpio2Config::StcRdsInterrupt);
+    set_stc_interrupts(regs, true);
+    previous_sysconfig1 != regs[Register::SYSCONFIG1]
+}
+
+pub(crate) fn enable_rds(regs: &mut Registers, mode: RdsMode) -> usize {
+    set_flag(
+        regs,
+        Register::POWERCFG,
+        BitFlags::RDSM,
+        mode == RdsMode::Verbose,
+    );
+    set_flag(regs, Register::SYSCONFIG1, BitFlags::RDS, true)
+}
+
+pub(crate) fn disable_rds(regs: &mut Registers) -> usize {
+    set_flag(regs, Register::SYSCONFIG1, BitFlags::RDS, false)
+}
+
+pub(crate) fn set_rds_interrupts(regs: &mut Registers, enable: bool) -> usize {
+    set_flag(regs, Register::SYSCONFIG1, BitFlags::RDSIEN, enable)
+}
+
+pub(crate) fn is_oscillator_enabled(regs: &Registers) -> bool {
+    (regs[Register::TEST1] & BitFlags::XOSCEN) != 0
+}
+
+pub(crate) fn is_audio_high_z_enabled(regs: &Registers) -> bool {
+    (regs[Register::TEST1] & BitFlags::AHIZEN) != 0
+}
+
+pub(crate) fn is_enabled(regs: &Registers) -> bool {
+    let powercfg = regs[Register::POWERCFG];
+    (powercfg & BitFlags::ENABLE) != 0 && (powercfg & BitFlags::DISABLE) == 0
+}
+
+pub(crate) fn is_muted(regs: &Registers) -> bool {
+    (regs[Register::POWERCFG] & BitFlags::DMUTE) == 0
+}
+
+/// Get the softmute rate and attenuation or `None` if softmute is disabled.
+pub(crate) fn softmute(regs: &Registers) -> Option<(SoftmuteRate, SoftmuteAttenuation)> {
+    if (regs[Register::POWERCFG] & BitFlags::DSMUTE) != 0 {
+        return None;
+    }
+    let sysconfig3 = regs[Register::SYSCONFIG3];
+    let rate = match (sysconfig3 >> 14) & 0b11 {
+        0 => SoftmuteRate::Fastest,
+        1 => SoftmuteRate::Fast,
+        2 => SoftmuteRate::Slow,
+        _ => SoftmuteRate::Slowest,
+    };
+    let attenuation = match (sysconfig3 >> 12) & 0b11 {
+        0 => SoftmuteAttenuation::Db16,
+        1 => SoftmuteAttenuation::Db14,
+        2 => SoftmuteAttenuation::Db12,
+        _ => SoftmuteAttenuation::Db10,
+    };
+    Some((rate, attenuation))
+}
+
+/// Get the forced output mode: Mono if the MONO bit is set, Stereo otherwise.
+pub(crate) fn configured_output_mode(regs: &Registers) -> OutputMode {
+    if (regs[Register::POWERCFG] & BitFlags::MONO) != 0 {
+        OutputMode::Mono
+    } else {
+        OutputMode::Stereo
+    }
+}
+
+pub(crate) fn deemphasis(regs: &Registers) -> DeEmphasis {
+    if (regs[Register::SYSCONFIG1] & BitFlags::DE) != 0 {
+        DeEmphasis::Us50
+    } else {
+        DeEmphasis::Us75
+    }
+}
+
+pub(crate) fn volume(regs: &Registers) -> Volume {
+    const EXTENDED: [Volume; 15] = [
+        Volume::Dbfsm58,
+        Volume::Dbfsm56,
+        Volume::Dbfsm54,
+        Volume::Dbfsm52,
+        Volume::Dbfsm50,
+        Volume::Dbfsm48,
+        Volume::Dbfsm46,
+        Volume::Dbfsm44,
+        Volume::Dbfsm42,
+        Volume::Dbfsm40,
+        Volume::Dbfsm38,
+        Volume::Dbfsm36,
+        Volume::Dbfsm34,
+        Volume::Dbfsm32,
+        Volume::Dbfsm30,
+    ];
+    const NORMAL: [Volume; 15] = [
+        Volume::Dbfsm28,
+        Volume::Dbfsm26,
+        Volume::Dbfsm24,
+        Volume::Dbfsm22,
+        Volume::Dbfsm20,
+        Volume::Dbfsm18,
+        Volume::Dbfsm16,
+        Volume::Dbfsm14,
+        Volume::Dbfsm12,
+        Volume::Dbfsm10,
+        Volume::Dbfsm8,
+        Volume::Dbfsm6,
+        Volume::Dbfsm4,
+        Volume::Dbfsm2,
+        Volume::Dbfs0,
+    ];
+    let mask = usize::from(regs[Register::SYSCONFIG2] & 0xF);
+    if mask == 0 {
+        Volume::Mute
+    } else if (regs[Register::SYSCONFIG3] & BitFlags::VOLEXT) != 0 {
+        EXTENDED[mask - 1]
+    } else {
+        NORMAL[mask - 1]
+    }
+}
+
+pub(crate) fn band(regs: &Registers) -> Band {
+    channel_plan(regs[Register::SYSCONFIG2]).band
+}
+
+pub(crate) fn channel_spacing(regs: &Registers) -> ChannelSpacing {
+    channel_plan(regs[Register::SYSCONFIG2]).spacing
+}
+
+/// Get the region matching the configured band, spacing and de-emphasis
+pub(crate) fn region(regs: &Registers) -> Option<Region> {
+    let (band, spacing, de) = (band(regs), channel_spacing(regs), deemphasis(regs));
+    Region::ALL.iter().copied().find(|region| {
+        region.band() == band && region.channel_spacing() == spacing && region.deemphasis() == de
+    })
+}
+
+pub(crate) fn is_stc_interrupts_enabled(regs: &Registers) -> bool {
+    (regs[Register::SYSCONFIG1] & BitFlags::STCIEN) != 0
+}
+
+/// The reserved value is reported as high impedance.
+pub(crate) fn gpio1(regs: &Registers) -> Gpio1Config {
+    match regs[Register::SYSCONFIG1] & 0b11 {
+        2 => Gpio1Config::Low,
+        3 => Gpio1Config::High,
+        _ => Gpio1Config::HighImpedance,
+    }
+}
+
+pub(crate) fn gpio2(regs: &Registers) -> Gpio2Config {
+    match (regs[Register::SYSCONFIG1] >> 2) & 0b11 {
+        0 => Gpio2Config::HighImpedance,
+        1 => Gpio2Config::StcRdsInterrupt,
+        2 => Gpio2Config::Low,
+        _ => Gpio2Config::High,
+    }
+}
+
+pub(crate) fn gpio3(regs: &Registers) -> Gpio3Config {
+    match (regs[Register::SYSCONFIG1] >> 4) & 0b11 {
+        0 => Gpio3Config::HighImpedance,
+        1 => Gpio3Config::MonoStereoIndicator,
+        2 => Gpio3Config::Low,
+        _ => Gpio3Config::High,
+    }
+}
+
+pub(crate) fn is_auto_gain_control_enabled(regs: &Registers) -> bool {
+    (regs[Register::SYSCONFIG1] & BitFlags::AGCD) == 0
+}
+
+pub(crate) fn blend_level(regs: &Registers) -> StereoToMonoBlendLevel {
+    match (regs[Register::SYSCONFIG1] >> 6) & 0b11 {
+        0 => StereoToMonoBlendLevel::Dbuv31_49,
+        1 => StereoToMonoBlendLevel::Dbuv37_55,
+        2 => StereoToMonoBlendLevel::Dbuv19_37,
+        _ => StereoToMonoBlendLevel::Dbuv25_43,
+    }
+}
+
+/// Get the (RSSI, SNR, FM impulse) seek thresholds.
+pub(crate) fn seek_config(regs: &Registers) -> (u8, SeekSnrThreshold, SeekFmImpulseThreshold) {
+    let rssi_threshold = (regs[Register::SYSCONFIG2] >> 8) as u8;
+    let sysconfig3 = regs[Register::SYSCONFIG3];
+    let snr = match ((sysconfig3 >> 4) & 0xF) as u8 {
+        0 => SeekSnrThreshold::Disabled,
+        v => SeekSnrThreshold::Enabled(v),
+    };
+    let cnt = match (sysconfig3 & 0xF) as u8 {
+        0 => SeekFmImpulseThreshold::Disabled,
+        v => SeekFmImpulseThreshold::Enabled(v),
+    };
+    (rssi_threshold, snr, cnt)
+}
+
+pub(crate) fn is_rds_enabled(regs: &Registers) -> bool {
+    (regs[Register::SYSCONFIG1] & BitFlags::RDS) != 0
+}
+
+pub(crate) fn rds_mode(regs: &Registers) -> RdsMode {
+    if (regs[Register::POWERCFG] & BitFlags::RDSM) != 0 {
+        RdsMode::Verbose
+    } else {
+        RdsMode::Standard
+    }
+}
+
+pub(crate) fn is_rds_interrupts_enabled(regs: &Registers) -> bool {
+    (regs[Register::SYSCONFIG1] & BitFlags::RDSIEN) != 0
+}
+
+/// Validate the configuration for the device and get the seek
+/// thresholds SYSCONFIG3 bits.
+pub(crate) fn check_config<E>(device: DeviceKind, config: &Config) -> Result<u16, Error<E>> {
+    let thresholds =
+        seek_thresholds_mask(config.seek_snr_threshold, config.seek_fm_impulse_threshold)?;
+    check_volume(device, config.volume)?;
+    Ok(thresholds)
+}
+
+/// Write the whole configuration into the register image.
+///
+/// The configuration must have been validated with `check_config()`.
+/// All control registers up to TEST1 need to be written.
+pub(crate) fn apply_config(regs: &mut Registers, config: &Config, seek_thresholds: u16) -> usize {
+    set_band(regs, config.band);
+    set_channel_spacing(regs, config.channel_spacing);
+    set_deemphasis(regs, config.deemphasis);
+    set_volume(regs, config.volume);
+    match config.softmute {
+        Some((rate, attenuation)) => enable_softmute(regs, rate, attenuation),
+        None => disable_softmute(regs),
+    };
+    set_stereo_to_mono_blend_level(regs, config.blend_level);
+    configure_seek(regs, config.seek_rssi_threshold, seek_thresholds);
+    set_gpio1(regs, config.gpio1);
+    set_gpio2(regs, config.gpio2);
+    set_gpio3(regs, config.gpio3);
+    set_auto_gain_control(regs, config.auto_gain_control);
+    set_flag(
+        regs,
+        Register::POWERCFG,
+        BitFlags::RDSM,
+        config.rds_mode == RdsMode::Verbose,
+    );
+    Register::TEST1
+}
+
+/// Decode the whole configuration from the register image.
+pub(crate) fn config(regs: &Registers) -> Config {
+    let (seek_rssi_threshold, seek_snr_threshold, seek_fm_impulse_threshold) = seek_config(regs);
+    Config {
+        band: band(regs),
+        channel_spacing: channel_spacing(regs),
+        deemphasis: deemphasis(regs),
+        volume: volume(regs),
+        softmute: softmute(regs),
+        blend_level: blend_level(regs),
+        seek_rssi_threshold,
+        seek_snr_threshold,
+        seek_fm_impulse_threshold,
+        gpio1: gpio1(regs),
+        gpio2: gpio2(regs),
+        gpio3: gpio3(regs),
+        auto_gain_control: is_auto_gain_control_enabled(regs),
+        rds_mode: rds_mode(regs),
+    }
+}
+
+/// Get the band and channel spacing from the SYSCONFIG2 value
+pub(crate) fn channel_plan(sysconfig2: u16) -> ChannelPlan {
+    let band = match (sysconfig2 >> 6) & 0b11 {
+        0 => Band::Mhz875_108,
+        1 => Band::Mhz76_108,
+        _ => Band::Mhz76_90,
+    };
+    let spacing = match (sysconfig2 >> 4) & 0b11 {
+        0 => ChannelSpacing::Khz200,
+        1 => ChannelSpacing::Khz100,
+        _ => ChannelSpacing::Khz50,
+    };
+    ChannelPlan::new(band, spacing)
+}
+
+/// Get the channel frequency from the READCHAN value
+/// taking the configured band and spacing into account.
+pub(crate) fn channel_khz(sysconfig2: u16, readchan: u16) -> Frequency {
+    channel_plan(sysconfig2).frequency_of_unchecked(readchan & 0x3FF)
+}
+
+/// Get the channel frequency in MHz from the READCHAN value
+/// taking the configured band and spacing into account.
+pub(crate) fn channel_mhz(sysconfig2: u16, readchan: u16) -> f32 {
+    channel_khz(sysconfig2, readchan).mhz()
+}
+
+pub(crate) fn output_mode(statusrssi: u16) -> OutputMode {
+    if (statusrssi & BitFlags::ST) != 0 {
+        OutputMode::Stereo
+    } else {
+        OutputMode::Mono
+    }
+}
+
+/// Get the RSSI in dBµV from the STATUSRSSI value
+pub(crate) fn rssi(statusrssi: u16) -> u8 {
+    (statusrssi & 0xFF) as u8
+}
+
+/// Decode the outcome of a seek from the register read that completed it.
+pub(crate) fn seek_outcome(regs: &Registers) -> SeekOutcome {
+    let tune = tune_outcome(regs);
+    SeekOutcome {
+        frequency: tune.frequency,
+        rssi: tune.rssi,
+        stereo: tune.stereo,
+        afc_railed: tune.afc_railed,
+        band_limit: (regs[Register::STATUSRSSI] & BitFlags::SF_BL) != 0,
+    }
+}
+
+/// Decode the outcome of a tuning from the register read that completed it.
+pub(crate) fn tune_outcome(regs: &Registers) -> TuneOutcome {
+    let status = status(regs[Register::STATUSRSSI], regs[Register::READCHAN]);
+    TuneOutcome {
+        frequency: channel_khz(regs[Register::SYSCONFIG2], status.channel),
+        rssi: status.rssi,
+        stereo: status.stereo,
+        afc_railed: status.afc_rail,
+    }
+}
+
+/// Decode the status from the STATUSRSSI and READCHAN values
+pub(crate) fn status(statusrssi: u16, readchan: u16) -> Status {
+    let is_set = |flag| (statusrssi & flag) != 0;
+    Status {
+        rds_ready: is_set(BitFlags::RDSR),
+        seek_tune_complete: is_set(BitFlags::STC),
+        seek_failed_or_band_limit: is_set(BitFlags::SF_BL),
+        afc_rail: is_set(BitFlags::AFCRL),
+        rds_synchronized: is_set(BitFlags::RDSS),
+        stereo: is_set(BitFlags::ST),
+        rssi: rssi(statusrssi),
+        channel: readchan & 0x3FF,
+        block_a_errors: get_block_errors(statusrssi, BitFlags::BLERA1, BitFlags::BLERA0),
+        block_b_errors: get_block_errors(readchan, BitFlags::BLERB1, BitFlags::BLERB0),
+        block_c_errors: get_block_errors(readchan, BitFlags::BLERC1, BitFlags::BLERC0),
+        block_d_errors: get_block_errors(readchan, BitFlags::BLERD1, BitFlags::BLERD0),
+    }
+}
+
+/// Decode the DEVICEID register into (part number, manufacturer ID)
+pub(crate) fn device_id(device_id: u16) -> (u8, u16) {
+    let pn = ((device_id & 0xF000) >> 12) as u8;
+    let mfid = device_id & 0xFFF;
+    (pn, mfid)
+}
+
+/// Decode the CHIPID register into (revision, device, firmware)
+pub(crate) fn chip_id(chip_id: u16) -> (u8, u8, u8) {
+    let rev = ((chip_id & 0xFC00) >> 10) as u8;
+    let dev = ((chip_id & 0x3C0) >> 6) as u8;
+    let firmware = (chip_id & 0x3F) as u8;
+    (rev, dev, firmware)
+}
+
+/// Decode the DEVICEID and CHIPID registers
+pub(crate) fn chip_info(regs: &Registers) -> ChipInfo {
+    let (pn, mfid) = device_id(regs[Register::DEVICE_ID]);
+    let (rev, dev, firmware) = chip_id(regs[Register::CHIP_ID]);
+    ChipInfo {
+        part_number: match pn {
+            0x1 => PartNumber::Si470x,
+            _ => PartNumber::Unknown(pn),
+        },
+        manufacturer: match mfid {
+            0x242 => Manufacturer::SiliconLabs,
+            _ => Manufacturer::Unknown(mfid),
+        },
+        revision: match rev {
+            0x04 => Revision::C,
+            _ => Revision::Unknown(rev),
+        },
+        device: match dev {
+            0b0000 => DeviceKind::Si4700,
+            0b1000 => DeviceKind::Si4701,
+            0b0001 => DeviceKind::Si4702,
+            0b1001 => DeviceKind::Si4703,
+            _ => DeviceKind::Unknown(dev),
+        },
+        firmware,
+    }
+}
+
+/// Select the driver device kind for the reported chip information
+///
+/// Before powerup, the Si4702/03 report the same device as the Si4700/01
+/// and no firmware version, so only the RDS support can be told apart.
+pub(crate) fn detected_device<E>(info: &ChipInfo) -> Result<DeviceKind, Error<E>> {
+    if !info.is_si470x() {
+        return Err(Error::UnsupportedDevice);
+    }
+    match info.device {
+        DeviceKind::Si4700 if info.firmware != 0 => Ok(DeviceKind::Si4700),
+        DeviceKind::Si4701 if info.firmware != 0 => Ok(DeviceKind::Si4701),
+        kind if kind.has_rds() => Ok(DeviceKind::Si4703),
+        DeviceKind::Unknown(_) => Err(Error::UnsupportedDevice),
+        _ => Ok(DeviceKind::Si4702),
+    }
+}
+
+fn set_flag(regs: &mut Registers, register: usize, flag: u16, enable: bool) -> usize {
+    if enable {
+        regs[register] |= flag;
+    } else {
+        regs[register] &= !flag;
+    }
+    register
+}
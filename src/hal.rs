@@ -0,0 +1,143 @@
+//! Supported `embedded-hal` versions
+//!
+//! The blocking driver works with `embedded-hal` 1.0 implementations and,
+//! with the `eh02` feature, with `embedded-hal` 0.2 implementations.
+//! The version is selected through the last type parameter of
+//! [`Si4703`](../struct.Si4703.html), which defaults to [`Eh1`].
+//! The I²C bus, pins and delays passed to a driver must implement the
+//! traits of the same version.
+//!
+//! The traits in this module are implemented for all types implementing
+//! the corresponding `embedded-hal` traits and do not need to be used
+//! directly.
+
+/// `embedded-hal` 1.0 (default)
+#[derive(Debug)]
+pub struct Eh1(());
+
+/// `embedded-hal` 0.2 (`eh02` feature)
+#[cfg(feature = "eh02")]
+#[derive(Debug)]
+pub struct Eh02(());
+
+/// I²C bus
+pub trait I2cBus<HAL> {
+    /// Error type
+    type Error;
+    /// Read bytes from the device
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error>;
+    /// Write bytes to the device
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// Input pin
+pub trait InputPin<HAL> {
+    /// Error type
+    type Error;
+    /// Whether the pin is high
+    fn is_high(&mut self) -> Result<bool, Self::Error>;
+}
+
+/// Output pin
+pub trait OutputPin<HAL> {
+    /// Error type
+    type Error;
+    /// Drive the pin low
+    fn set_low(&mut self) -> Result<(), Self::Error>;
+    /// Drive the pin high
+    fn set_high(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Delay
+pub trait Delay<HAL> {
+    /// Wait for the given number of milliseconds
+    fn delay_ms(&mut self, ms: u32);
+}
+
+impl<T: embedded_hal::i2c::I2c> I2cBus<Eh1> for T {
+    type Error = T::Error;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        embedded_hal::i2c::I2c::read(self, address, buffer)
+    }
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        embedded_hal::i2c::I2c::write(self, address, bytes)
+    }
+}
+
+impl<T: embedded_hal::digital::InputPin> InputPin<Eh1> for T {
+    type Error = T::Error;
+
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        embedded_hal::digital::InputPin::is_high(self)
+    }
+}
+
+impl<T: embedded_hal::digital::OutputPin> OutputPin<Eh1> for T {
+    type Error = T::Error;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        embedded_hal::digital::OutputPin::set_low(self)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        embedded_hal::digital::OutputPin::set_high(self)
+    }
+}
+
+impl<T: embedded_hal::delay::DelayNs> Delay<Eh1> for T {
+    fn delay_ms(&mut self, ms: u32) {
+        embedded_hal::delay::DelayNs::delay_ms(self, ms)
+    }
+}
+
+#[cfg(feature = "eh02")]
+mod eh02 {
+    use super::{Delay, Eh02, I2cBus, InputPin, OutputPin};
+    use embedded_hal_0_2::{
+        blocking::{delay::DelayMs, i2c},
+        digital::v2,
+    };
+
+    impl<T, E> I2cBus<Eh02> for T
+    where
+        T: i2c::Read<Error = E> + i2c::Write<Error = E>,
+    {
+        type Error = E;
+
+        fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), E> {
+            i2c::Read::read(self, address, buffer)
+        }
+
+        fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), E> {
+            i2c::Write::write(self, address, bytes)
+        }
+    }
+
+    impl<T: v2::InputPin> InputPin<Eh02> for T {
+        type Error = T::Error;
+
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            v2::InputPin::is_high(self)
+        }
+    }
+
+    impl<T: v2::OutputPin> OutputPin<Eh02> for T {
+        type Error = T::Error;
+
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            v2::OutputPin::set_low(self)
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            v2::OutputPin::set_high(self)
+        }
+    }
+
+    impl<T: DelayMs<u32>> Delay<Eh02> for T {
+        fn delay_ms(&mut self, ms: u32) {
+            DelayMs::delay_ms(self, ms)
+        }
+    }
+}
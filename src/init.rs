@@ -0,0 +1,146 @@
+use crate::{
+    fields::{self, Registers, OSCILLATOR_STARTUP_MS, POWERUP_MS},
+    hal::{Delay, I2cBus, OutputPin},
+    private, reset, Config, InitError, OperationState, ScanState, Si4703,
+};
+
+/// Check that the device and chip ID match the expected device.
+pub(crate) fn check_id<IC: private::ExpectedDevice, E>(
+    regs: &Registers,
+) -> Result<(), InitError<E>> {
+    let info = fields::chip_info(regs);
+    if info.is_si470x() && info.device == IC::DEVICE {
+        Ok(())
+    } else {
+        Err(InitError::UnexpectedDevice(info))
+    }
+}
+
+impl<I2C, E, IC, HAL> Si4703<I2C, IC, HAL>
+where
+    I2C: I2cBus<HAL, Error = E>,
+    IC: private::ExpectedDevice,
+{
+    /// Power up the device and apply the configuration
+    ///
+    /// This performs the powerup sequence: the oscillator is enabled and
+    /// 500ms are waited for it to stabilize, then the device is enabled and
+    /// 110ms are waited for it to power up. Afterwards, the device ID and
+    /// chip ID are verified and the configuration is applied.
+    ///
+    /// The reset and I2C communication selection must be done beforehand.
+    /// See [`reset_and_select_i2c_method1()`](fn.reset_and_select_i2c_method1.html)
+    /// or use [`init_with_reset()`](#method.init_with_reset) instead.
+    ///
+    /// An invalid configuration is rejected before communicating with the
+    /// device.
+    pub fn init<D: Delay<HAL>>(
+        &mut self,
+        delay: &mut D,
+        config: &Config,
+    ) -> Result<(), InitError<E>> {
+        let thresholds =
+            fields::check_config(IC::DEVICE, config).map_err(InitError::ApplyConfig)?;
+        self.read_all_registers()
+            .and_then(|_| self.enable_oscillator())
+            .map_err(InitError::EnableOscillator)?;
+        delay.delay_ms(OSCILLATOR_STARTUP_MS);
+        self.enable().map_err(InitError::Enable)?;
+        delay.delay_ms(POWERUP_MS);
+        let regs = self.read_all_registers().map_err(InitError::ReadId)?;
+        check_id::<IC, E>(&regs)?;
+        self.update_registers(|regs| fields::apply_config(regs, config, thresholds))
+            .map_err(InitError::ApplyConfig)
+    }
+
+    /// Reset the device, select I2C communication, power it up and apply
+    /// the configuration
+    ///
+    /// This performs the reset with
+    /// [`reset_and_select_i2c_method1()`](fn.reset_and_select_i2c_method1.html)
+    /// followed by [`init()`](#method.init). The SDA pin is set high
+    /// after the reset to release the line for the I2C bus.
+    /// Any ongoing seek, tune or band scan and the settings saved by
+    /// [`power_down()`](#method.power_down) are forgotten after the reset.
+    ///
+    /// The SDA pin must be an open-drain output that can be driven while
+    /// the I2C bus is owned by this driver. Otherwise perform the reset
+    /// before creating the I2C bus and use [`init()`](#method.init).
+    pub fn init_with_reset<PinE, RST, SDA, D>(
+        &mut self,
+        rst: &mut RST,
+        sda: &mut SDA,
+        delay: &mut D,
+        config: &Config,
+    ) -> Result<(), InitError<E, PinE>>
+    where
+        RST: OutputPin<HAL, Error = PinE>,
+        SDA: OutputPin<HAL, Error = PinE>,
+        D: Delay<HAL>,
+    {
+        reset::method1(rst, sda, delay)
+            .and_then(|_| sda.set_high())
+            .map_err(InitError::Reset)?;
+        self.operation_state = OperationState::Idle;
+        self.scan_state = ScanState::Idle;
+        self.power_down_state = None;
+        self.shadow_valid = false;
+        self.init(delay, config).map_err(InitError::with_pin_error)
+    }
+}
+
+impl<E> InitError<E> {
+    pub(crate) fn with_pin_error<PinE>(self) -> InitError<E, PinE> {
+        match self {
+            InitError::Reset(e) => match e {},
+            InitError::EnableOscillator(e) => InitError::EnableOscillator(e),
+            InitError::Enable(e) => InitError::Enable(e),
+            InitError::ReadId(e) => InitError::ReadId(e),
+            InitError::UnexpectedDevice(info) => InitError::UnexpectedDevice(info),
+            InitError::ApplyConfig(e) => InitError::ApplyConfig(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ic, ChipInfo, DeviceKind, Manufacturer, PartNumber, Register, Revision};
+
+    fn registers(device_id: u16, chip_id: u16) -> Registers {
+        let mut regs = [0; 16];
+        regs[Register::DEVICE_ID] = device_id;
+        regs[Register::CHIP_ID] = chip_id;
+        regs
+    }
+
+    #[test]
+    fn accepts_expected_devices() {
+        assert!(check_id::<ic::Si4703, ()>(&registers(0x1242, 0x1253)).is_ok());
+        assert!(check_id::<ic::Si4702, ()>(&registers(0x1242, 0x1053)).is_ok());
+        assert!(check_id::<ic::Si4701, ()>(&registers(0x1242, 0x1213)).is_ok());
+        assert!(check_id::<ic::Si4700, ()>(&registers(0x1242, 0x1013)).is_ok());
+    }
+
+    #[test]
+    fn rejects_other_device() {
+        let result = check_id::<ic::Si4703, ()>(&registers(0x1242, 0x1053));
+        assert!(matches!(
+            result,
+            Err(InitError::UnexpectedDevice(ChipInfo {
+                part_number: PartNumber::Si470x,
+                manufacturer: Manufacturer::SiliconLabs,
+                revision: Revision::C,
+                device: DeviceKind::Si4702,
+                firmware: 0x13,
+            }))
+        ));
+    }
+
+    #[test]
+    fn rejects_unpowered_or_missing_device() {
+        assert!(check_id::<ic::Si4703, ()>(&registers(0x1242, 0x1000)).is_err());
+        assert!(check_id::<ic::Si4703, ()>(&registers(0xFFFF, 0xFFFF)).is_err());
+        assert!(check_id::<ic::Si4703, ()>(&registers(0, 0)).is_err());
+    }
+}
@@ -1,32 +1,63 @@
-//! This is a platform agnostic Rust driver for the Si4702 and Si4703 FM radio
-//! turners (receivers) using the [`embedded-hal`] traits and I2C.
+//! This is a platform agnostic Rust driver for the Si4700, Si4701, Si4702 and
+//! Si4703 FM radio turners (receivers) using the [`embedded-hal`] traits and I2C.
+//!
+//! The driver is based on the `embedded-hal` 1.0 traits. `embedded-hal` 0.2
+//! implementations are supported through the `eh02` feature: drivers created
+//! with the constructors in the [`eh02`](eh02/index.html) module take the
+//! `embedded-hal` 0.2 I2C bus, pins and delays directly.
+//! An asynchronous driver based on `embedded-hal-async` is available
+//! through the `async` feature (requires Rust 1.75 or later).
+//! See the [`asynch`](asynch/index.html) module.
 //!
 //! [`embedded-hal`]: https://github.com/rust-embedded/embedded-hal
 //!
 //! This driver allows you to:
+//! - Power up, verify the device and apply a configuration. See: [`init()`].
+//! - Reset the device before powering it up. See: [`init_with_reset()`].
 //! - Enable/disable the device. See: [`enable()`].
+//! - Power down/up following the recommended sequence. See: [`power_down()`].
 //! - Mute/unmute. See: [`mute()`].
 //! - Configure seek. See: [`configure_seek()`].
 //! - Seek with/without STC interrupts. See: [`seek_with_stc_int_pin()`].
 //! - Tune a frequency with/without STC interrupts. See: [`tune_with_stc_int_pin()`].
+//! - Tune a frequency with a rounding policy and get the tuned frequency. See: [`tune_rounded()`].
+//! - Get the frequency, RSSI and stereo/AFC rail/band limit flags of a finished seek/tune. See: [`seek()`].
+//! - Seek/tune waiting for completion with a timeout. See: [`seek_blocking()`].
+//! - Cancel an ongoing seek/tune. See: [`cancel_seek()`].
+//! - Query the ongoing seek/tune operation. See: [`current_operation()`].
+//! - Scan the whole band collecting the receivable stations. See: [`scan_band()`].
 //! - Set volume. See: [`set_volume()`].
 //! - Set band. See: [`set_band()`].
 //! - Set channel spacing. See: [`set_channel_spacing()`].
+//! - Set band, channel spacing and de-emphasis for a region at once. See: [`set_region()`].
+//! - Convert between raw channels and frequencies without a device. See: [`ChannelPlan`].
 //! - Set the GPIO1, GPIO2 and GPIO3 function/status. See: [`set_gpio1()`].
 //! - Enable/disable softmute. See: [`enable_softmute()`].
 //! - Enable/disable auto gain control. See: [`enable_auto_gain_control()`].
 //! - Enable/disable oscillator. See: [`enable_oscillator()`].
 //! - Enable/disable STC interrupts. See: [`enable_stc_interrupts()`].
-//! - Enable/disable audio High-Z. See: [`enable_audio_high_z()`].
+//! - Enable/disable audio High-Z (only on Si4702/03). See: [`enable_audio_high_z()`].
 //! - Set de-emphasis. See: [`set_deemphasis()`].
 //! - Set stereo to mono blend level. See: [`set_stereo_to_mono_blend_level()`].
 //! - Set stereo/mono output mode. See: [`set_output_mode()`].
 //! - Read output mode. See: [`output_mode()`].
+//! - Read the received signal strength (RSSI). See: [`rssi()`].
+//! - Read the whole device status at once. See: [`status()`].
+//! - Read the configured settings like volume, band or GPIO configuration. See: [`volume()`].
+//! - Apply/read the whole device configuration at once. See: [`apply_config()`].
+//! - Change several settings in a single transaction. See: [`modify()`].
 //! - Read channel. See: [`channel()`].
+//! - Read the exact channel frequency without floating point operations. See: [`channel_khz()`].
 //! - Read device ID. See: [`device_id()`].
 //! - Read chip ID. See: [`chip_id()`].
+//! - Read the decoded chip identification. See: [`chip_info()`].
+//! - Detect the device at runtime. See: [`detect()`].
+//! - Read/write any register with typed access to all its fields. See: [`read_register()`].
+//! - Dump all registers decoded field by field for diagnostics. See: [`read_registers()`].
+//! - Save/restore the device configuration across resets. See: [`snapshot()`].
+//! - Refresh the cached control registers from the device. See: [`resync()`].
 //! - Reset and select I2C communication using several methods. See: [`reset_and_select_i2c_method1()`].
-//! - RDS/RBDS (only on Si4703):
+//! - RDS/RBDS (only on Si4701/03):
 //!     - Enable/disable RDS. See: [`enable_rds()`].
 //!     - Enable/disable RDS interrupts. See: [`enable_rds_interrupts()`].
 //!     - Read whether a new RDS group is ready. See: [`rds_ready()`].
@@ -35,14 +66,25 @@
 //!     - Decode RDS radio text from RDS data. See: [`get_rds_radio_text()`].
 //!     - Fill char array with decoded RDS radio text from RDS data. See: [`fill_with_rds_radio_text()`].
 //!
+//! [`init()`]: struct.Si4703.html#method.init
+//! [`init_with_reset()`]: struct.Si4703.html#method.init_with_reset
 //! [`enable()`]: struct.Si4703.html#method.enable
+//! [`power_down()`]: struct.Si4703.html#method.power_down
 //! [`mute()`]: struct.Si4703.html#method.mute
 //! [`configure_seek()`]: struct.Si4703.html#method.configure_seek
 //! [`seek_with_stc_int_pin()`]: struct.Si4703.html#method.seek_with_stc_int_pin
 //! [`tune_with_stc_int_pin()`]: struct.Si4703.html#method.tune_with_stc_int_pin
+//! [`tune_rounded()`]: struct.Si4703.html#method.tune_rounded
+//! [`seek()`]: struct.Si4703.html#method.seek
+//! [`seek_blocking()`]: struct.Si4703.html#method.seek_blocking
+//! [`cancel_seek()`]: struct.Si4703.html#method.cancel_seek
+//! [`current_operation()`]: struct.Si4703.html#method.current_operation
+//! [`scan_band()`]: struct.Si4703.html#method.scan_band
 //! [`set_volume()`]: struct.Si4703.html#method.set_volume
 //! [`set_band()`]: struct.Si4703.html#method.set_band
 //! [`set_channel_spacing()`]: struct.Si4703.html#method.set_channel_spacing
+//! [`set_region()`]: struct.Si4703.html#method.set_region
+//! [`ChannelPlan`]: struct.ChannelPlan.html
 //! [`set_gpio1()`]: struct.Si4703.html#method.set_gpio1
 //! [`enable_softmute()`]: struct.Si4703.html#method.enable_softmute
 //! [`enable_auto_gain_control()`]: struct.Si4703.html#method.enable_auto_gain_control
@@ -53,9 +95,21 @@
 //! [`set_stereo_to_mono_blend_level()`]: struct.Si4703.html#method.set_stereo_to_mono_blend_level
 //! [`set_output_mode()`]: struct.Si4703.html#method.set_output_mode
 //! [`output_mode()`]: struct.Si4703.html#method.output_mode
+//! [`rssi()`]: struct.Si4703.html#method.rssi
+//! [`status()`]: struct.Si4703.html#method.status
+//! [`volume()`]: struct.Si4703.html#method.volume
+//! [`apply_config()`]: struct.Si4703.html#method.apply_config
+//! [`modify()`]: struct.Si4703.html#method.modify
 //! [`channel()`]: struct.Si4703.html#method.channel
+//! [`channel_khz()`]: struct.Si4703.html#method.channel_khz
 //! [`device_id()`]: struct.Si4703.html#method.device_id
 //! [`chip_id()`]: struct.Si4703.html#method.chip_id
+//! [`chip_info()`]: struct.Si4703.html#method.chip_info
+//! [`detect()`]: fn.detect.html
+//! [`read_register()`]: struct.Si4703.html#method.read_register
+//! [`read_registers()`]: struct.Si4703.html#method.read_registers
+//! [`snapshot()`]: struct.Si4703.html#method.snapshot
+//! [`resync()`]: struct.Si4703.html#method.resync
 //! [`reset_and_select_i2c_method1()`]: struct.Si4703.html#method.reset_and_select_i2c_method1
 //! [`enable_rds()`]: struct.Si4703.html#method.enable_rds
 //! [`enable_rds_interrupts()`]: struct.Si4703.html#method.enable_rds_interrupts
@@ -112,15 +166,25 @@
 //!
 //! ### Seek a channel, listen for 5 seconds, then seek again
 //!
+//! This example uses [`linux-embedded-hal`], which implements the
+//! `embedded-hal` 0.2 traits, through the [`eh02`] module (requires the
+//! `eh02` feature). With HAL implementations of the `embedded-hal` 1.0
+//! traits, use `Si4703::new()` and `reset_and_select_i2c_method1()` instead.
+//!
+//! [`linux-embedded-hal`]: https://github.com/rust-embedded/linux-embedded-hal
+//! [`eh02`]: eh02/index.html
+//!
 //! ```no_run
-//! use embedded_hal::blocking::delay::DelayMs;
+//! # fn main() {
+//! # #[cfg(feature = "eh02")]
+//! # {
+//! use embedded_hal_0_2::blocking::delay::DelayMs;
 //! use linux_embedded_hal::{Delay, I2cdev, Pin};
 //! use si4703::{
-//!     reset_and_select_i2c_method1, ChannelSpacing, DeEmphasis, ErrorWithPin, SeekDirection,
+//!     eh02::reset_and_select_i2c_method1, ChannelSpacing, Config, DeEmphasis, SeekDirection,
 //!     SeekMode, Si4703, Volume,
 //! };
 //!
-//! # fn main() {
 //! let mut delay = Delay {};
 //! {
 //!     // Reset and communication protocol selection must be done beforehand
@@ -129,75 +193,116 @@
 //!     reset_and_select_i2c_method1(&mut rst, &mut sda, &mut delay).unwrap();
 //! }
 //! let dev = I2cdev::new("/dev/i2c-1").unwrap();
-//! let mut radio = Si4703::new(dev);
-//! radio.enable_oscillator().unwrap();
-//! // Wait for the oscillator to stabilize
-//! delay.delay_ms(500_u16);
-//! radio.enable().unwrap();
-//! // Wait for powerup
-//! delay.delay_ms(110_u16);
-//!
-//! radio.set_volume(Volume::Dbfsm28).unwrap();
-//! radio.set_deemphasis(DeEmphasis::Us50).unwrap();
-//! radio.set_channel_spacing(ChannelSpacing::Khz100).unwrap();
+//! let mut radio = Si4703::new_eh02(dev);
+//! // Power up, verify the device and apply the configuration
+//! let config = Config {
+//!     volume: Volume::Dbfsm28,
+//!     deemphasis: DeEmphasis::Us50,
+//!     channel_spacing: ChannelSpacing::Khz100,
+//!     ..Config::default()
+//! };
+//! radio.init(&mut delay, &config).unwrap();
 //! radio.unmute().unwrap();
 //!
 //! // use STC interrupt pin method
-//! let stc_int = Pin::new(27);
+//! let mut stc_int = Pin::new(27);
 //! loop {
-//!     match radio.seek_with_stc_int_pin(SeekMode::Wrap, SeekDirection::Up, &stc_int) {
+//!     match radio.seek_with_stc_int_pin(SeekMode::Wrap, SeekDirection::Up, &mut stc_int) {
 //!         Err(nb::Error::WouldBlock) => {
 //!             let channel = radio.channel().unwrap_or(-1.0);
 //!             println!("Trying channel at {:1} MHz", channel);
 //!         }
-//!         Err(nb::Error::Other(ErrorWithPin::SeekFailed)) => {
-//!             println!("Seek Failed");
-//!         }
 //!         Err(e) => {
 //!             println!("Error: {:?}", e);
 //!         }
+//!         Ok(outcome) if outcome.found() => {
+//!             println!("Found channel at {}", outcome.frequency);
+//!             delay.delay_ms(5000_u32); // listen for 5 seconds, then seek again
+//!         }
 //!         Ok(_) => {
-//!             let channel = radio.channel().unwrap_or(-1.0);
-//!             println!("Found channel at {:1} MHz", channel);
-//!             delay.delay_ms(5000_u16); // listen for 5 seconds, then seek again
+//!             println!("Seek Failed");
 //!         }
 //!     }
-//!     delay.delay_ms(50_u8);
+//!     delay.delay_ms(50_u32);
 //! }
 //! # }
+//! # }
 //! ```
 //!
 
 #![deny(unsafe_code, missing_docs)]
 #![no_std]
 
+#[cfg(feature = "async")]
+pub mod asynch;
+mod channel_plan;
+pub use crate::channel_plan::{ChannelPlan, Channels};
+mod detect;
+pub use crate::detect::{detect, DetectedDevice};
 mod device_impl;
+#[cfg(feature = "eh02")]
+pub mod eh02;
+mod fields;
+pub mod hal;
+mod init;
+mod modify;
+pub use crate::modify::Modifier;
+mod power;
 mod rds;
 pub use crate::rds::{fill_with_rds_radio_text, get_rds_radio_text};
 mod register_access;
+pub mod registers;
 mod reset;
+mod scan;
 mod seek;
+mod snapshot;
 use crate::register_access::{BitFlags, Register};
 pub use crate::reset::{
     reset_and_select_i2c_method1, reset_and_select_i2c_method1_with_gpio3,
     reset_and_select_i2c_method2,
 };
+pub use crate::snapshot::Snapshot;
 mod tune;
 mod types;
-use crate::types::OperationState;
 pub use crate::types::{
-    ic, marker, Band, ChannelSpacing, DeEmphasis, Error, ErrorWithPin, Gpio1Config, Gpio2Config,
-    Gpio3Config, OutputMode, RdsBlockData, RdsBlockErrors, RdsData, RdsMode, RdsRadioText,
-    RdsRadioTextData, SeekDirection, SeekFmImpulseThreshold, SeekMode, SeekSnrThreshold, Si4703,
-    SoftmuteAttenuation, SoftmuteRate, StereoToMonoBlendLevel, TuneChannel, Volume,
+    ic, marker, Band, ChannelSpacing, ChipInfo, Config, DeEmphasis, DeviceKind, Error,
+    ErrorWithPin, Frequency, Gpio1Config, Gpio2Config, Gpio3Config, InitError, Manufacturer,
+    Operation, OutputMode, PartNumber, RdsBlockData, RdsBlockErrors, RdsData, RdsMode,
+    RdsRadioText, RdsRadioTextData, Region, Revision, Rounding, SeekDirection,
+    SeekFmImpulseThreshold, SeekMode, SeekOutcome, SeekSnrThreshold, Si4703, SoftmuteAttenuation,
+    SoftmuteRate, Station, Status, StereoToMonoBlendLevel, TuneChannel, TuneOutcome, Volume,
 };
+use crate::types::{OperationState, PowerDownState, ScanState};
 
+impl marker::WithRds for ic::Si4701 {}
 impl marker::WithRds for ic::Si4703 {}
+impl marker::WithAudioHighZ for ic::Si4702 {}
+impl marker::WithAudioHighZ for ic::Si4703 {}
 
 mod private {
-    use super::ic;
+    use super::{ic, DeviceKind};
     pub trait Sealed {}
 
+    impl Sealed for ic::Si4700 {}
+    impl Sealed for ic::Si4701 {}
     impl Sealed for ic::Si4702 {}
     impl Sealed for ic::Si4703 {}
+
+    /// Device identification expected after powerup
+    pub trait ExpectedDevice {
+        const DEVICE: DeviceKind;
+    }
+
+    impl ExpectedDevice for ic::Si4700 {
+        const DEVICE: DeviceKind = DeviceKind::Si4700;
+    }
+    impl ExpectedDevice for ic::Si4701 {
+        const DEVICE: DeviceKind = DeviceKind::Si4701;
+    }
+    impl ExpectedDevice for ic::Si4702 {
+        const DEVICE: DeviceKind = DeviceKind::Si4702;
+    }
+    impl ExpectedDevice for ic::Si4703 {
+        const DEVICE: DeviceKind = DeviceKind::Si4703;
+    }
 }
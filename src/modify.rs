@@ -0,0 +1,263 @@
+use crate::{
+    fields, hal::I2cBus, marker, private, Band, ChannelSpacing, DeEmphasis, Error, Gpio1Config,
+    Gpio2Config, Gpio3Config, OutputMode, RdsMode, Region, Register, SeekFmImpulseThreshold,
+    SeekSnrThreshold, Si4703, SoftmuteAttenuation, SoftmuteRate, StereoToMonoBlendLevel, Volume,
+};
+use core::marker::PhantomData;
+
+/// Collects changes to the control registers
+///
+/// The changes are written to the device at once at the end of
+/// [`modify()`](struct.Si4703.html#method.modify).
+#[derive(Debug)]
+pub struct Modifier<IC> {
+    regs: [u16; 16],
+    invalid_input: bool,
+    _ic: PhantomData<IC>,
+}
+
+impl<IC> Modifier<IC> {
+    pub(crate) fn new(regs: [u16; 16]) -> Self {
+        Modifier {
+            regs,
+            invalid_input: false,
+            _ic: PhantomData,
+        }
+    }
+
+    /// Get the modified register image and the index of the last register
+    /// that differs from `original`, if any.
+    pub(crate) fn finish<E>(
+        self,
+        original: &[u16; 16],
+    ) -> Result<([u16; 16], Option<usize>), Error<E>> {
+        if self.invalid_input {
+            return Err(Error::InvalidInputData);
+        }
+        let last = (Register::POWERCFG..=Register::TEST1)
+            .rev()
+            .find(|&i| self.regs[i] != original[i]);
+        Ok((self.regs, last))
+    }
+
+    fn apply<F: FnOnce(&mut [u16; 16]) -> usize>(&mut self, f: F) -> &mut Self {
+        f(&mut self.regs);
+        self
+    }
+
+    /// Enable the oscillator.
+    pub fn enable_oscillator(&mut self) -> &mut Self {
+        self.apply(|regs| fields::set_oscillator(regs, true))
+    }
+
+    /// Enable the device.
+    pub fn enable(&mut self) -> &mut Self {
+        self.apply(|regs| fields::set_enable(regs, true))
+    }
+
+    /// Disable the device (power down).
+    pub fn disable(&mut self) -> &mut Self {
+        self.apply(|regs| fields::set_enable(regs, false))
+    }
+
+    /// Unmute (disable mute)
+    pub fn unmute(&mut self) -> &mut Self {
+        self.apply(|regs| fields::set_mute(regs, false))
+    }
+
+    /// Mute (enable mute)
+    pub fn mute(&mut self) -> &mut Self {
+        self.apply(|regs| fields::set_mute(regs, true))
+    }
+
+    /// Enable softmute
+    pub fn enable_softmute(
+        &mut self,
+        rate: SoftmuteRate,
+        attenuation: SoftmuteAttenuation,
+    ) -> &mut Self {
+        self.apply(|regs| fields::enable_softmute(regs, rate, attenuation))
+    }
+
+    /// Disable softmute
+    pub fn disable_softmute(&mut self) -> &mut Self {
+        self.apply(fields::disable_softmute)
+    }
+
+    /// Set output mode: Stereo/Mono
+    pub fn set_output_mode(&mut self, mode: OutputMode) -> &mut Self {
+        self.apply(|regs| fields::set_output_mode(regs, mode))
+    }
+
+    /// Set the band, channel spacing and de-emphasis used in a region
+    pub fn set_region(&mut self, region: Region) -> &mut Self {
+        self.apply(|regs| fields::set_region(regs, region))
+    }
+
+    /// Set de-emphasis
+    pub fn set_deemphasis(&mut self, de: DeEmphasis) -> &mut Self {
+        self.apply(|regs| fields::set_deemphasis(regs, de))
+    }
+
+    /// Set the volume
+    ///
+    /// This will automatically activate or deactivate the extended volume
+    /// range as appropriate. The extended volume range is only available on
+    /// the Si4702/03, otherwise `modify()` returns `Error::InvalidInputData`
+    /// without writing anything.
+    pub fn set_volume(&mut self, volume: Volume) -> &mut Self
+    where
+        IC: private::ExpectedDevice,
+    {
+        match fields::check_volume::<()>(IC::DEVICE, volume) {
+            Ok(()) => self.apply(|regs| fields::set_volume(regs, volume)),
+            Err(_) => {
+                self.invalid_input = true;
+                self
+            }
+        }
+    }
+
+    /// Set band
+    pub fn set_band(&mut self, band: Band) -> &mut Self {
+        self.apply(|regs| fields::set_band(regs, band))
+    }
+
+    /// Set channel spacing
+    pub fn set_channel_spacing(&mut self, spacing: ChannelSpacing) -> &mut Self {
+        self.apply(|regs| fields::set_channel_spacing(regs, spacing))
+    }
+
+    /// Enable generating STC interrupts.
+    pub fn enable_stc_interrupts(&mut self) -> &mut Self {
+        self.apply(|regs| fields::set_stc_interrupts(regs, true))
+    }
+
+    /// Disable generating STC interrupts.
+    pub fn disable_stc_interrupts(&mut self) -> &mut Self {
+        self.apply(|regs| fields::set_stc_interrupts(regs, false))
+    }
+
+    /// Set the GPIO1
+    pub fn set_gpio1(&mut self, config: Gpio1Config) -> &mut Self {
+        self.apply(|regs| fields::set_gpio1(regs, config))
+    }
+
+    /// Set GPIO2 function / status
+    pub fn set_gpio2(&mut self, config: Gpio2Config) -> &mut Self {
+        self.apply(|regs| fields::set_gpio2(regs, config))
+    }
+
+    /// Set the GPIO3
+    pub fn set_gpio3(&mut self, config: Gpio3Config) -> &mut Self {
+        self.apply(|regs| fields::set_gpio3(regs, config))
+    }
+
+    /// Enable automatic gain control (default).
+    pub fn enable_auto_gain_control(&mut self) -> &mut Self {
+        self.apply(|regs| fields::set_auto_gain_control(regs, true))
+    }
+
+    /// Disable automatic gain control.
+    pub fn disable_auto_gain_control(&mut self) -> &mut Self {
+        self.apply(|regs| fields::set_auto_gain_control(regs, false))
+    }
+
+    /// Set the stereo to mono blend level
+    pub fn set_stereo_to_mono_blend_level(&mut self, level: StereoToMonoBlendLevel) -> &mut Self {
+        self.apply(|regs| fields::set_stereo_to_mono_blend_level(regs, level))
+    }
+
+    /// Configure seek RSSI, SNR and FM impulse detection thresholds
+    ///
+    /// Invalid thresholds make `modify()` return `Error::InvalidInputData`
+    /// without writing anything.
+    pub fn configure_seek(
+        &mut self,
+        rssi_threshold: u8,
+        snr_threshold: SeekSnrThreshold,
+        fm_impulse_threshold: SeekFmImpulseThreshold,
+    ) -> &mut Self {
+        match fields::seek_thresholds_mask::<()>(snr_threshold, fm_impulse_threshold) {
+            Ok(thresholds) => {
+                self.apply(|regs| fields::configure_seek(regs, rssi_threshold, thresholds))
+            }
+            Err(_) => {
+                self.invalid_input = true;
+                self
+            }
+        }
+    }
+}
+
+impl<IC: marker::WithAudioHighZ> Modifier<IC> {
+    /// Enable the audio High-Z.
+    pub fn enable_audio_high_z(&mut self) -> &mut Self {
+        self.apply(|regs| fields::set_audio_high_z(regs, true))
+    }
+
+    /// Disable the audio High-Z.
+    pub fn disable_audio_high_z(&mut self) -> &mut Self {
+        self.apply(|regs| fields::set_audio_high_z(regs, false))
+    }
+}
+
+impl<IC: marker::WithRds> Modifier<IC> {
+    /// Enable RDS.
+    pub fn enable_rds(&mut self, mode: RdsMode) -> &mut Self {
+        self.apply(|regs| fields::enable_rds(regs, mode))
+    }
+
+    /// Disable RDS.
+    pub fn disable_rds(&mut self) -> &mut Self {
+        self.apply(fields::disable_rds)
+    }
+
+    /// Enable RDS interrupts.
+    pub fn enable_rds_interrupts(&mut self) -> &mut Self {
+        self.apply(|regs| fields::set_rds_interrupts(regs, true))
+    }
+
+    /// Disable RDS interrupts.
+    pub fn disable_rds_interrupts(&mut self) -> &mut Self {
+        self.apply(|regs| fields::set_rds_interrupts(regs, false))
+    }
+}
+
+impl<I2C, E, IC, HAL> Si4703<I2C, IC, HAL>
+where
+    I2C: I2cBus<HAL, Error = E>,
+{
+    /// Change several settings in a single transaction
+    ///
+    /// The changes done in the closure are collected and written at once.
+    /// Only the registers from POWERCFG up to the last changed one are
+    /// written. Nothing is written if nothing changed.
+    ///
+    /// ```no_run
+    /// # use embedded_hal::i2c::I2c;
+    /// # fn example<I2C: I2c>(mut radio: si4703::Si4703<I2C, si4703::ic::Si4703>) {
+    /// use si4703::{OutputMode, Volume};
+    ///
+    /// radio
+    ///     .modify(|m| {
+    ///         m.set_volume(Volume::Dbfsm28)
+    ///             .unmute()
+    ///             .set_output_mode(OutputMode::Mono);
+    ///     })
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn modify<F>(&mut self, f: F) -> Result<(), Error<E>>
+    where
+        F: FnOnce(&mut Modifier<IC>),
+    {
+        let original = self.cached_registers()?;
+        let mut modifier = Modifier::new(original);
+        f(&mut modifier);
+        match modifier.finish(&original)? {
+            (regs, Some(last)) => self.write_registers(&regs[..=last]),
+            (_, None) => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{
+    fields::{self, POWERUP_MS},
+    hal::{Delay, I2cBus},
+    marker, Error, Si4703,
+};
+
+impl<I2C, E, IC, HAL> Si4703<I2C, IC, HAL>
+where
+    I2C: I2cBus<HAL, Error = E>,
+{
+    /// Power down the device following the sequence in the programmer's guide
+    ///
+    /// First RDS is disabled and the GPIOs are set to high impedance.
+    /// Then the device is disabled.
+    ///
+    /// The previous RDS and GPIO settings are restored by
+    /// [`power_up()`](#method.power_up).
+    pub fn power_down(&mut self) -> Result<(), Error<E>> {
+        self.power_down_with(false)
+    }
+
+    fn power_down_with(&mut self, audio_high_z: bool) -> Result<(), Error<E>> {
+        let mut regs = self.cached_registers()?;
+        let (state, last) = fields::prepare_power_down(&mut regs, audio_high_z);
+        self.write_registers(&regs[..=last])?;
+        if self.power_down_state.is_none() {
+            self.power_down_state = Some(state);
+        }
+        self.update_registers(|regs| fields::set_enable(regs, false))
+    }
+
+    /// Power up the device after a [`power_down()`](#method.power_down)
+    ///
+    /// The RDS, GPIO and audio high-Z settings from before the power down
+    /// are restored in the same write that enables the device. Afterwards,
+    /// the provided delay is used to wait for the powerup time (110ms).
+    pub fn power_up<D: Delay<HAL>>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        let state = self.power_down_state;
+        self.update_registers(|regs| fields::power_up(regs, state.as_ref()))?;
+        self.power_down_state = None;
+        delay.delay_ms(POWERUP_MS);
+        Ok(())
+    }
+}
+
+impl<I2C, E, IC, HAL> Si4703<I2C, IC, HAL>
+where
+    I2C: I2cBus<HAL, Error = E>,
+    IC: marker::WithAudioHighZ,
+{
+    /// Power down the device setting the audio outputs to high impedance
+    ///
+    /// Like [`power_down()`](#method.power_down) but the audio outputs are
+    /// set to high impedance as well before disabling the device.
+    /// The previous audio high-Z setting is restored by
+    /// [`power_up()`](#method.power_up).
+    pub fn power_down_with_audio_high_z(&mut self) -> Result<(), Error<E>> {
+        self.power_down_with(true)
+    }
+}
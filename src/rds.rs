@@ -1,44 +1,46 @@
 use crate::{
-    marker, BitFlags, Error, RdsBlockData, RdsBlockErrors, RdsData, RdsMode, RdsRadioText,
-    RdsRadioTextData, Register, Si4703,
+    fields, hal::I2cBus, marker, BitFlags, Error, RdsBlockData, RdsBlockErrors, RdsData, RdsMode,
+    RdsRadioText, RdsRadioTextData, Register, Si4703,
 };
-use embedded_hal::blocking::i2c;
 
-impl<I2C, E, IC> Si4703<I2C, IC>
+impl<I2C, E, IC, HAL> Si4703<I2C, IC, HAL>
 where
-    I2C: i2c::Write<Error = E> + i2c::Read<Error = E>,
+    I2C: I2cBus<HAL, Error = E>,
     IC: marker::WithRds,
 {
     /// Enable RDS.
     pub fn enable_rds(&mut self, mode: RdsMode) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        regs[Register::SYSCONFIG1] |= BitFlags::RDS;
-        match mode {
-            RdsMode::Standard => regs[Register::POWERCFG] &= !BitFlags::RDSM,
-            RdsMode::Verbose => regs[Register::POWERCFG] |= BitFlags::RDSM,
-        }
-        self.write_registers(&regs[0..=Register::SYSCONFIG1])
+        self.update_registers(|regs| fields::enable_rds(regs, mode))
     }
 
     /// Disable RDS.
     pub fn disable_rds(&mut self) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        regs[Register::SYSCONFIG1] &= !BitFlags::RDS;
-        self.write_registers(&regs[0..=Register::SYSCONFIG1])
+        self.update_registers(fields::disable_rds)
     }
 
     /// Enable RDS interrupts.
     pub fn enable_rds_interrupts(&mut self) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        regs[Register::SYSCONFIG1] |= BitFlags::RDSIEN;
-        self.write_registers(&regs[0..=Register::SYSCONFIG1])
+        self.update_registers(|regs| fields::set_rds_interrupts(regs, true))
     }
 
     /// Disable RDS interrupts.
     pub fn disable_rds_interrupts(&mut self) -> Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
-        regs[Register::SYSCONFIG1] &= !BitFlags::RDSIEN;
-        self.write_registers(&regs[0..=Register::SYSCONFIG1])
+        self.update_registers(|regs| fields::set_rds_interrupts(regs, false))
+    }
+
+    /// Get whether RDS is enabled.
+    pub fn is_rds_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_rds_enabled(&self.cached_registers()?))
+    }
+
+    /// Get the RDS mode
+    pub fn rds_mode(&mut self) -> Result<RdsMode, Error<E>> {
+        Ok(fields::rds_mode(&self.cached_registers()?))
+    }
+
+    /// Get whether RDS interrupts are enabled.
+    pub fn is_rds_interrupts_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(fields::is_rds_interrupts_enabled(&self.cached_registers()?))
     }
 
     /// Get whether a new RDS group is ready.
@@ -56,34 +58,39 @@ where
     /// Get RDS data.
     pub fn rds_data(&mut self) -> Result<RdsData, Error<E>> {
         let regs = self.read_rds()?;
-        let status = regs[Register::STATUSRSSI];
-        let blera = get_block_errors(status, BitFlags::BLERA1, BitFlags::BLERA0);
-        let readchan = regs[Register::READCHAN];
-        let blerb = get_block_errors(readchan, BitFlags::BLERB1, BitFlags::BLERB0);
-        let blerc = get_block_errors(readchan, BitFlags::BLERC1, BitFlags::BLERC0);
-        let blerd = get_block_errors(readchan, BitFlags::BLERD1, BitFlags::BLERD0);
-        Ok(RdsData {
-            a: RdsBlockData {
-                data: regs[Register::RDSA],
-                errors: blera,
-            },
-            b: RdsBlockData {
-                data: regs[Register::RDSB],
-                errors: blerb,
-            },
-            c: RdsBlockData {
-                data: regs[Register::RDSC],
-                errors: blerc,
-            },
-            d: RdsBlockData {
-                data: regs[Register::RDSD],
-                errors: blerd,
-            },
-        })
+        Ok(decode_rds_data(&regs))
+    }
+}
+
+/// Decode the RDS blocks and their errors from the register values.
+pub(crate) fn decode_rds_data(regs: &[u16; 16]) -> RdsData {
+    let status = regs[Register::STATUSRSSI];
+    let blera = get_block_errors(status, BitFlags::BLERA1, BitFlags::BLERA0);
+    let readchan = regs[Register::READCHAN];
+    let blerb = get_block_errors(readchan, BitFlags::BLERB1, BitFlags::BLERB0);
+    let blerc = get_block_errors(readchan, BitFlags::BLERC1, BitFlags::BLERC0);
+    let blerd = get_block_errors(readchan, BitFlags::BLERD1, BitFlags::BLERD0);
+    RdsData {
+        a: RdsBlockData {
+            data: regs[Register::RDSA],
+            errors: blera,
+        },
+        b: RdsBlockData {
+            data: regs[Register::RDSB],
+            errors: blerb,
+        },
+        c: RdsBlockData {
+            data: regs[Register::RDSC],
+            errors: blerc,
+        },
+        d: RdsBlockData {
+            data: regs[Register::RDSD],
+            errors: blerd,
+        },
     }
 }
 
-fn get_block_errors(data: u16, bitmask1: u16, bitmask0: u16) -> RdsBlockErrors {
+pub(crate) fn get_block_errors(data: u16, bitmask1: u16, bitmask0: u16) -> RdsBlockErrors {
     match ((data & bitmask1) != 0, (data & bitmask0) != 0) {
         (false, false) => RdsBlockErrors::None,
         (false, true) => RdsBlockErrors::OneOrTwo,
@@ -1,7 +1,6 @@
-use crate::{Error, Si4703};
-use embedded_hal::blocking::i2c;
+use crate::{hal::I2cBus, Error, Si4703};
 
-const DEVICE_ADDRESS: u8 = 0x10;
+pub(crate) const DEVICE_ADDRESS: u8 = 0x10;
 
 pub struct Register;
 impl Register {
@@ -57,72 +56,108 @@ impl BitFlags {
     pub const BLERD0: u16 = 1 << 10;
 }
 
-impl<I2C, E, IC> Si4703<I2C, IC>
+impl<I2C, E, IC, HAL> Si4703<I2C, IC, HAL>
 where
-    I2C: i2c::Write<Error = E> + i2c::Read<Error = E>,
+    I2C: I2cBus<HAL, Error = E>,
 {
     pub(crate) fn read_status(&mut self) -> Result<u16, Error<E>> {
-        let mut data = [0; 4];
-        self.i2c
-            .read(DEVICE_ADDRESS, &mut data)
-            .map_err(Error::I2C)?;
-        Ok(u16::from(data[0]) << 8 | u16::from(data[1]))
+        let regs = self.read_some_registers(2)?;
+        Ok(regs[Register::STATUSRSSI])
     }
 
     pub(crate) fn read_rds(&mut self) -> Result<[u16; 16], Error<E>> {
-        self.read_some_registers_bare_err(6).map_err(Error::I2C)
+        self.read_some_registers(6)
     }
 
-    pub(crate) fn read_powercfg(&mut self) -> Result<u16, Error<E>> {
-        self.read_powercfg_bare_err().map_err(Error::I2C)
+    /// Get the control registers (POWERCFG to TEST1) from the shadow copy.
+    ///
+    /// The shadow copy is populated from the device on first use.
+    pub(crate) fn cached_registers(&mut self) -> Result<[u16; 16], Error<E>> {
+        if !self.shadow_valid {
+            self.read_all_registers()?;
+        }
+        Ok(self.shadow)
     }
 
-    pub(crate) fn read_powercfg_bare_err(&mut self) -> Result<u16, E> {
-        let registers = self.read_some_registers_bare_err(9)?;
-        Ok(registers[Register::POWERCFG])
+    /// Get the cached control registers together with the STATUSRSSI and
+    /// READCHAN registers read from the device.
+    ///
+    /// Only the two status registers are read if the shadow copy is valid.
+    pub(crate) fn read_status_registers(&mut self) -> Result<[u16; 16], Error<E>> {
+        if !self.shadow_valid {
+            return self.read_all_registers();
+        }
+        let status = self.read_some_registers(2)?;
+        let mut regs = self.shadow;
+        regs[Register::STATUSRSSI] = status[Register::STATUSRSSI];
+        regs[Register::READCHAN] = status[Register::READCHAN];
+        Ok(regs)
     }
 
-    pub(crate) fn read_some_registers_bare_err(&mut self, count: usize) -> Result<[u16; 16], E> {
+    /// Like [`read_status_registers()`](#method.read_status_registers) but
+    /// the POWERCFG and CHANNEL registers are read from the device as well.
+    ///
+    /// The read wraps around from STATUSRSSI to CHANNEL. The shadow copy of
+    /// POWERCFG and CHANNEL is updated.
+    pub(crate) fn read_operation_registers(&mut self) -> Result<[u16; 16], Error<E>> {
+        if !self.shadow_valid {
+            return self.read_all_registers();
+        }
+        let read = self.read_some_registers(10)?;
+        self.shadow[Register::POWERCFG] = read[Register::POWERCFG];
+        self.shadow[Register::CHANNEL] = read[Register::CHANNEL];
+        let mut regs = self.shadow;
+        regs[Register::STATUSRSSI] = read[Register::STATUSRSSI];
+        regs[Register::READCHAN] = read[Register::READCHAN];
+        Ok(regs)
+    }
+
+    /// Modify the cached control registers and write them to the device
+    /// up to the register index returned by `f`.
+    pub(crate) fn update_registers<F>(&mut self, f: F) -> Result<(), Error<E>>
+    where
+        F: FnOnce(&mut [u16; 16]) -> usize,
+    {
+        let mut regs = self.cached_registers()?;
+        let last = f(&mut regs);
+        self.write_registers(&regs[..=last])
+    }
+
+    pub(crate) fn read_some_registers(&mut self, count: usize) -> Result<[u16; 16], Error<E>> {
         const OFFSET: usize = 0xA;
         let mut data = [0; 32];
-        self.i2c.read(DEVICE_ADDRESS, &mut data[..count * 2])?;
+        self.i2c
+            .read(DEVICE_ADDRESS, &mut data[..count * 2])
+            .map_err(Error::I2C)?;
         Ok(to_registers(data, OFFSET))
     }
 
-    pub(crate) fn read_registers(&mut self) -> Result<[u16; 16], Error<E>> {
-        self.read_registers_bare_err().map_err(Error::I2C)
-    }
-
-    pub(crate) fn read_registers_bare_err(&mut self) -> Result<[u16; 16], E> {
+    pub(crate) fn read_all_registers(&mut self) -> Result<[u16; 16], Error<E>> {
         const OFFSET: usize = 0xA;
         let mut data = [0; 32];
-        self.i2c.read(DEVICE_ADDRESS, &mut data)?;
+        self.i2c
+            .read(DEVICE_ADDRESS, &mut data)
+            .map_err(Error::I2C)?;
         let registers = to_registers(data, OFFSET);
+        self.shadow[Register::POWERCFG..=Register::TEST1]
+            .copy_from_slice(&registers[Register::POWERCFG..=Register::TEST1]);
+        self.shadow_valid = true;
         Ok(registers)
     }
 
-    pub(crate) fn write_powercfg(&mut self, value: u16) -> Result<(), Error<E>> {
-        self.write_powercfg_bare_err(value).map_err(Error::I2C)
-    }
-
-    pub(crate) fn write_powercfg_bare_err(&mut self, value: u16) -> Result<(), E> {
-        let data = [(value >> 8) as u8, value as u8];
-        self.i2c.write(DEVICE_ADDRESS, &data)
-    }
-
     pub(crate) fn write_registers(&mut self, registers: &[u16]) -> Result<(), Error<E>> {
-        self.write_registers_bare_err(registers).map_err(Error::I2C)
-    }
-
-    pub(crate) fn write_registers_bare_err(&mut self, registers: &[u16]) -> Result<(), E> {
         const OFFSET: usize = 0x2;
         let data = from_registers(registers, OFFSET);
         self.i2c
             .write(DEVICE_ADDRESS, &data[..((registers.len() - OFFSET) * 2)])
+            .map_err(Error::I2C)?;
+        let last = registers.len() - 1;
+        self.shadow[Register::POWERCFG..=last].copy_from_slice(&registers[Register::POWERCFG..]);
+        Ok(())
     }
 }
 
-fn to_registers(data: [u8; 32], offset: usize) -> [u16; 16] {
+pub(crate) fn to_registers(data: [u8; 32], offset: usize) -> [u16; 16] {
     let mut registers = [0; 16];
     for i in 0..registers.len() {
         registers[(i + offset) % registers.len()] =
@@ -131,7 +166,7 @@ fn to_registers(data: [u8; 32], offset: usize) -> [u16; 16] {
     registers
 }
 
-fn from_registers(registers: &[u16], offset: usize) -> [u8; 32] {
+pub(crate) fn from_registers(registers: &[u16], offset: usize) -> [u8; 32] {
     let mut data = [0; 32];
     for i in 0..registers.len() {
         let reg = registers[(i + offset) % registers.len()];
@@ -143,7 +178,6 @@ fn from_registers(registers: &[u16], offset: usize) -> [u8; 32] {
 
 #[cfg(test)]
 mod tests {
-    extern crate embedded_hal_mock as hal;
     use super::*;
     const DATA: [u8; 32] = [
         0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0xA, 0xB, 0xC, 0xD, 0xE, 0xF, 0x10, 0x11, 0x12, 0x13, 0x14,
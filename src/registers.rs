@@ -0,0 +1,539 @@
+//! Typed register values
+//!
+//! These types allow accessing every field of the device registers,
+//! including those not otherwise supported by this driver.
+//! Register values can be read and written with
+//! [`read_register()`](../struct.Si4703.html#method.read_register) and
+//! [`write_register()`](../struct.Si4703.html#method.write_register).
+//!
+//! ```
+//! use si4703::registers::{RegisterValue, SysConfig3};
+//!
+//! let mut sysconfig3 = SysConfig3::from(0);
+//! sysconfig3.set_smuter(2);
+//! sysconfig3.set_volext(true);
+//! assert_eq!(0x8100, u16::from(sysconfig3));
+//! assert_eq!(0x06, SysConfig3::ADDRESS);
+//! ```
+
+use crate::private;
+use core::fmt;
+
+/// Register value with a known address
+pub trait RegisterValue: private::Sealed + Copy + From<u16> + Into<u16> {
+    /// Register address
+    const ADDRESS: usize;
+    /// Register name as used in the datasheet
+    const NAME: &'static str;
+}
+
+/// Register that can be written
+///
+/// Only the control registers POWERCFG to TEST1 can be written.
+pub trait WritableRegister: RegisterValue {}
+
+/// Conversion of a field value from/into the register bits
+trait FieldValue {
+    fn from_bits(bits: u16) -> Self;
+    fn into_bits(self) -> u16;
+}
+
+impl FieldValue for bool {
+    fn from_bits(bits: u16) -> Self {
+        bits != 0
+    }
+    fn into_bits(self) -> u16 {
+        u16::from(self)
+    }
+}
+
+impl FieldValue for u8 {
+    fn from_bits(bits: u16) -> Self {
+        bits as u8
+    }
+    fn into_bits(self) -> u16 {
+        u16::from(self)
+    }
+}
+
+impl FieldValue for u16 {
+    fn from_bits(bits: u16) -> Self {
+        bits
+    }
+    fn into_bits(self) -> u16 {
+        self
+    }
+}
+
+/// Mask for the bits `msb` to `lsb`
+const fn mask(msb: u16, lsb: u16) -> u16 {
+    (((1_u32 << (msb - lsb + 1)) - 1) as u16) << lsb
+}
+
+/// Write the fields given as (name, msb, lsb) as `NAME=value`
+/// separated by spaces.
+fn write_fields(
+    f: &mut fmt::Formatter<'_>,
+    value: u16,
+    fields: &[(&str, u16, u16)],
+) -> fmt::Result {
+    for (i, &(name, msb, lsb)) in fields.iter().enumerate() {
+        if i != 0 {
+            f.write_str(" ")?;
+        }
+        write!(f, "{}={}", name, (value & mask(msb, lsb)) >> lsb)?;
+    }
+    Ok(())
+}
+
+macro_rules! register {
+    (
+        $(#[$doc:meta])*
+        $name:ident, $address:expr, $register_name:literal, {
+            $(
+                $field:ident: $getter:ident, $setter:ident: $type:ty = [$msb:expr, $lsb:expr],
+                $description:literal;
+            )*
+        }
+    ) => {
+        $(#[$doc])*
+        #[derive(Default, Clone, Copy, PartialEq, Eq)]
+        pub struct $name(u16);
+
+        impl $name {
+            $(
+                #[doc = concat!("Get the ", $description, " (", stringify!($field), ")")]
+                pub fn $getter(&self) -> $type {
+                    <$type as FieldValue>::from_bits((self.0 & mask($msb, $lsb)) >> $lsb)
+                }
+
+                #[doc = concat!("Set the ", $description, " (", stringify!($field), ")")]
+                ///
+                /// Bits not fitting in the field are ignored.
+                pub fn $setter(&mut self, value: $type) -> &mut Self {
+                    const MASK: u16 = mask($msb, $lsb);
+                    self.0 = (self.0 & !MASK) | ((value.into_bits() << $lsb) & MASK);
+                    self
+                }
+            )*
+        }
+
+        impl From<u16> for $name {
+            fn from(value: u16) -> Self {
+                $name(value)
+            }
+        }
+
+        impl From<$name> for u16 {
+            fn from(register: $name) -> Self {
+                register.0
+            }
+        }
+
+        impl private::Sealed for $name {}
+
+        impl RegisterValue for $name {
+            const ADDRESS: usize = $address;
+            const NAME: &'static str = $register_name;
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write_fields(f, self.0, &[$((stringify!($field), $msb, $lsb)),*])
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let mut s = f.debug_struct(stringify!($name));
+                $(
+                    s.field(stringify!($field), &self.$getter());
+                )*
+                s.finish()
+            }
+        }
+    };
+}
+
+register!(
+    /// Device ID register (0x00)
+    DeviceId, 0x00, "DEVICEID", {
+        PN: pn, set_pn: u8 = [15, 12],
+            "part number";
+        MFGID: mfgid, set_mfgid: u16 = [11, 0],
+            "manufacturer ID";
+    }
+);
+
+register!(
+    /// Chip ID register (0x01)
+    ChipId, 0x01, "CHIPID", {
+        REV: rev, set_rev: u8 = [15, 10],
+            "chip revision";
+        DEV: dev, set_dev: u8 = [9, 6],
+            "device";
+        FIRMWARE: firmware, set_firmware: u8 = [5, 0],
+            "firmware version";
+    }
+);
+
+register!(
+    /// Power configuration register (0x02)
+    PowerCfg, 0x02, "POWERCFG", {
+        DSMUTE: dsmute, set_dsmute: bool = [15, 15],
+            "softmute disable";
+        DMUTE: dmute, set_dmute: bool = [14, 14],
+            "mute disable";
+        MONO: mono, set_mono: bool = [13, 13],
+            "mono select";
+        RDSM: rdsm, set_rdsm: bool = [11, 11],
+            "RDS mode";
+        SKMODE: skmode, set_skmode: bool = [10, 10],
+            "seek mode";
+        SEEKUP: seekup, set_seekup: bool = [9, 9],
+            "seek direction";
+        SEEK: seek, set_seek: bool = [8, 8],
+            "seek";
+        DISABLE: disable, set_disable: bool = [6, 6],
+            "powerup disable";
+        ENABLE: enable, set_enable: bool = [0, 0],
+            "powerup enable";
+    }
+);
+
+register!(
+    /// Channel register (0x03)
+    Channel, 0x03, "CHANNEL", {
+        TUNE: tune, set_tune: bool = [15, 15],
+            "tune";
+        CHAN: chan, set_chan: u16 = [9, 0],
+            "channel select";
+    }
+);
+
+register!(
+    /// System configuration 1 register (0x04)
+    SysConfig1, 0x04, "SYSCONFIG1", {
+        RDSIEN: rdsien, set_rdsien: bool = [15, 15],
+            "RDS interrupt enable";
+        STCIEN: stcien, set_stcien: bool = [14, 14],
+            "seek/tune complete interrupt enable";
+        RDS: rds, set_rds: bool = [12, 12],
+            "RDS enable";
+        DE: de, set_de: bool = [11, 11],
+            "de-emphasis";
+        AGCD: agcd, set_agcd: bool = [10, 10],
+            "AGC disable";
+        BLNDADJ: blndadj, set_blndadj: u8 = [7, 6],
+            "stereo/mono blend level adjustment";
+        GPIO3: gpio3, set_gpio3: u8 = [5, 4],
+            "general purpose I/O 3";
+        GPIO2: gpio2, set_gpio2: u8 = [3, 2],
+            "general purpose I/O 2";
+        GPIO1: gpio1, set_gpio1: u8 = [1, 0],
+            "general purpose I/O 1";
+    }
+);
+
+register!(
+    /// System configuration 2 register (0x05)
+    SysConfig2, 0x05, "SYSCONFIG2", {
+        SEEKTH: seekth, set_seekth: u8 = [15, 8],
+            "RSSI seek threshold";
+        BAND: band, set_band: u8 = [7, 6],
+            "band select";
+        SPACE: space, set_space: u8 = [5, 4],
+            "channel spacing";
+        VOLUME: volume, set_volume: u8 = [3, 0],
+            "volume";
+    }
+);
+
+register!(
+    /// System configuration 3 register (0x06)
+    SysConfig3, 0x06, "SYSCONFIG3", {
+        SMUTER: smuter, set_smuter: u8 = [15, 14],
+            "softmute attack/recover rate";
+        SMUTEA: smutea, set_smutea: u8 = [13, 12],
+            "softmute attenuation";
+        VOLEXT: volext, set_volext: bool = [8, 8],
+            "extended volume range";
+        SKSNR: sksnr, set_sksnr: u8 = [7, 4],
+            "seek SNR threshold";
+        SKCNT: skcnt, set_skcnt: u8 = [3, 0],
+            "seek FM impulse detection threshold";
+    }
+);
+
+register!(
+    /// Test 1 register (0x07)
+    Test1, 0x07, "TEST1", {
+        XOSCEN: xoscen, set_xoscen: bool = [15, 15],
+            "crystal oscillator enable";
+        AHIZEN: ahizen, set_ahizen: bool = [14, 14],
+            "audio high-Z enable";
+        RESERVED: reserved, set_reserved: u16 = [13, 0],
+            "reserved bits";
+    }
+);
+
+register!(
+    /// Test 2 register (0x08)
+    Test2, 0x08, "TEST2", {
+        RESERVED: reserved, set_reserved: u16 = [15, 0],
+            "reserved bits";
+    }
+);
+
+register!(
+    /// Boot configuration register (0x09)
+    BootConfig, 0x09, "BOOTCONFIG", {
+        RESERVED: reserved, set_reserved: u16 = [15, 0],
+            "reserved bits";
+    }
+);
+
+register!(
+    /// Status RSSI register (0x0A)
+    StatusRssi, 0x0A, "STATUSRSSI", {
+        RDSR: rdsr, set_rdsr: bool = [15, 15],
+            "RDS ready";
+        STC: stc, set_stc: bool = [14, 14],
+            "seek/tune complete";
+        SF_BL: sf_bl, set_sf_bl: bool = [13, 13],
+            "seek fail/band limit";
+        AFCRL: afcrl, set_afcrl: bool = [12, 12],
+            "AFC rail";
+        RDSS: rdss, set_rdss: bool = [11, 11],
+            "RDS synchronized";
+        BLERA: blera, set_blera: u8 = [10, 9],
+            "RDS block A errors";
+        ST: st, set_st: bool = [8, 8],
+            "stereo indicator";
+        RSSI: rssi, set_rssi: u8 = [7, 0],
+            "received signal strength indicator";
+    }
+);
+
+register!(
+    /// Read channel register (0x0B)
+    ReadChan, 0x0B, "READCHAN", {
+        BLERB: blerb, set_blerb: u8 = [15, 14],
+            "RDS block B errors";
+        BLERC: blerc, set_blerc: u8 = [13, 12],
+            "RDS block C errors";
+        BLERD: blerd, set_blerd: u8 = [11, 10],
+            "RDS block D errors";
+        READCHAN: readchan, set_readchan: u16 = [9, 0],
+            "read channel";
+    }
+);
+
+register!(
+    /// RDS block A data register (0x0C)
+    RdsA, 0x0C, "RDSA", {
+        RDSA: rdsa, set_rdsa: u16 = [15, 0],
+            "RDS block A data";
+    }
+);
+
+register!(
+    /// RDS block B data register (0x0D)
+    RdsB, 0x0D, "RDSB", {
+        RDSB: rdsb, set_rdsb: u16 = [15, 0],
+            "RDS block B data";
+    }
+);
+
+register!(
+    /// RDS block C data register (0x0E)
+    RdsC, 0x0E, "RDSC", {
+        RDSC: rdsc, set_rdsc: u16 = [15, 0],
+            "RDS block C data";
+    }
+);
+
+register!(
+    /// RDS block D data register (0x0F)
+    RdsD, 0x0F, "RDSD", {
+        RDSD: rdsd, set_rdsd: u16 = [15, 0],
+            "RDS block D data";
+    }
+);
+
+impl WritableRegister for PowerCfg {}
+impl WritableRegister for Channel {}
+impl WritableRegister for SysConfig1 {}
+impl WritableRegister for SysConfig2 {}
+impl WritableRegister for SysConfig3 {}
+impl WritableRegister for Test1 {}
+
+/// Contents of all the device registers
+///
+/// This can be obtained with
+/// [`read_registers()`](../struct.Si4703.html#method.read_registers)
+/// and is meant for diagnostics.
+///
+/// The `Display` implementation prints one line per register with its
+/// address, name, raw value and the value of every field.
+/// The `Debug` implementation prints every field of every register.
+/// Both only rely on `core::fmt`, so the dump can be written to any
+/// `core::fmt::Write` implementation in `no_std` environments.
+///
+/// ```
+/// use si4703::registers::{PowerCfg, RegisterDump};
+///
+/// let mut registers = [0; 16];
+/// registers[0x02] = 0x4001;
+/// let dump = RegisterDump::from(registers);
+/// assert!(dump.get::<PowerCfg>().dmute());
+///
+/// let text = format!("{}", dump);
+/// assert!(text.contains(
+///     "0x02 POWERCFG   0x4001 DSMUTE=0 DMUTE=1 MONO=0 RDSM=0 SKMODE=0 \
+///      SEEKUP=0 SEEK=0 DISABLE=0 ENABLE=1\n"
+/// ));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct RegisterDump([u16; 16]);
+
+impl RegisterDump {
+    /// Get the value of a register
+    pub fn get<R: RegisterValue>(&self) -> R {
+        R::from(self.0[R::ADDRESS])
+    }
+
+    /// Get the raw register values indexed by register address
+    pub fn raw(&self) -> [u16; 16] {
+        self.0
+    }
+
+    fn write_line<R: RegisterValue + fmt::Display>(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        writeln!(
+            f,
+            "0x{:02X} {:<10} 0x{:04X} {}",
+            R::ADDRESS,
+            R::NAME,
+            self.0[R::ADDRESS],
+            self.get::<R>()
+        )
+    }
+}
+
+impl From<[u16; 16]> for RegisterDump {
+    fn from(registers: [u16; 16]) -> Self {
+        RegisterDump(registers)
+    }
+}
+
+impl fmt::Display for RegisterDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_line::<DeviceId>(f)?;
+        self.write_line::<ChipId>(f)?;
+        self.write_line::<PowerCfg>(f)?;
+        self.write_line::<Channel>(f)?;
+        self.write_line::<SysConfig1>(f)?;
+        self.write_line::<SysConfig2>(f)?;
+        self.write_line::<SysConfig3>(f)?;
+        self.write_line::<Test1>(f)?;
+        self.write_line::<Test2>(f)?;
+        self.write_line::<BootConfig>(f)?;
+        self.write_line::<StatusRssi>(f)?;
+        self.write_line::<ReadChan>(f)?;
+        self.write_line::<RdsA>(f)?;
+        self.write_line::<RdsB>(f)?;
+        self.write_line::<RdsC>(f)?;
+        self.write_line::<RdsD>(f)
+    }
+}
+
+impl fmt::Debug for RegisterDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RegisterDump")
+            .field(DeviceId::NAME, &self.get::<DeviceId>())
+            .field(ChipId::NAME, &self.get::<ChipId>())
+            .field(PowerCfg::NAME, &self.get::<PowerCfg>())
+            .field(Channel::NAME, &self.get::<Channel>())
+            .field(SysConfig1::NAME, &self.get::<SysConfig1>())
+            .field(SysConfig2::NAME, &self.get::<SysConfig2>())
+            .field(SysConfig3::NAME, &self.get::<SysConfig3>())
+            .field(Test1::NAME, &self.get::<Test1>())
+            .field(Test2::NAME, &self.get::<Test2>())
+            .field(BootConfig::NAME, &self.get::<BootConfig>())
+            .field(StatusRssi::NAME, &self.get::<StatusRssi>())
+            .field(ReadChan::NAME, &self.get::<ReadChan>())
+            .field(RdsA::NAME, &self.get::<RdsA>())
+            .field(RdsB::NAME, &self.get::<RdsB>())
+            .field(RdsC::NAME, &self.get::<RdsC>())
+            .field(RdsD::NAME, &self.get::<RdsD>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_convert_from_and_into_u16() {
+        assert_eq!(0xABCD, u16::from(PowerCfg::from(0xABCD)));
+        assert_eq!(0x1234, u16::from(ReadChan::from(0x1234)));
+    }
+
+    #[test]
+    fn can_get_flags() {
+        let powercfg = PowerCfg::from(0x8001);
+        assert!(powercfg.dsmute());
+        assert!(!powercfg.dmute());
+        assert!(powercfg.enable());
+    }
+
+    #[test]
+    fn can_get_fields() {
+        let sysconfig2 = SysConfig2::from(0xAB5F);
+        assert_eq!(0xAB, sysconfig2.seekth());
+        assert_eq!(1, sysconfig2.band());
+        assert_eq!(1, sysconfig2.space());
+        assert_eq!(0xF, sysconfig2.volume());
+        assert_eq!(0x3FF, Channel::from(0xFFFF).chan());
+    }
+
+    #[test]
+    fn can_set_fields_keeping_others() {
+        let mut sysconfig1 = SysConfig1::from(0xFFFF);
+        sysconfig1.set_gpio2(0).set_rds(false);
+        assert_eq!(0xEFF3, u16::from(sysconfig1));
+        sysconfig1.set_gpio2(1).set_rds(true);
+        assert_eq!(0xFFF7, u16::from(sysconfig1));
+    }
+
+    #[test]
+    fn set_ignores_too_big_values() {
+        let mut sysconfig1 = SysConfig1::default();
+        sysconfig1.set_gpio1(0xFF);
+        assert_eq!(3, u16::from(sysconfig1));
+    }
+
+    #[test]
+    fn addresses_are_correct() {
+        assert_eq!(0x00, DeviceId::ADDRESS);
+        assert_eq!(0x01, ChipId::ADDRESS);
+        assert_eq!(0x02, PowerCfg::ADDRESS);
+        assert_eq!(0x03, Channel::ADDRESS);
+        assert_eq!(0x04, SysConfig1::ADDRESS);
+        assert_eq!(0x05, SysConfig2::ADDRESS);
+        assert_eq!(0x06, SysConfig3::ADDRESS);
+        assert_eq!(0x07, Test1::ADDRESS);
+        assert_eq!(0x08, Test2::ADDRESS);
+        assert_eq!(0x09, BootConfig::ADDRESS);
+        assert_eq!(0x0A, StatusRssi::ADDRESS);
+        assert_eq!(0x0B, ReadChan::ADDRESS);
+        assert_eq!(0x0C, RdsA::ADDRESS);
+        assert_eq!(0x0D, RdsB::ADDRESS);
+        assert_eq!(0x0E, RdsC::ADDRESS);
+        assert_eq!(0x0F, RdsD::ADDRESS);
+    }
+}
@@ -1,4 +1,4 @@
-use embedded_hal::{blocking::delay::DelayMs, digital::v2::OutputPin};
+use crate::hal::{Delay, Eh1, OutputPin};
 
 /// Reset the device and select I2C communication (method 1, no GPIO3)
 ///
@@ -8,17 +8,15 @@ use embedded_hal::{blocking::delay::DelayMs, digital::v2::OutputPin};
 /// to perform the reset.
 pub fn reset_and_select_i2c_method1<
     E,
-    RST: OutputPin<Error = E>,
-    SDA: OutputPin<Error = E>,
-    DELAY: DelayMs<u8>,
+    RST: embedded_hal::digital::OutputPin<Error = E>,
+    SDA: embedded_hal::digital::OutputPin<Error = E>,
+    DELAY: embedded_hal::delay::DelayNs,
 >(
     rst: &mut RST,
     sda: &mut SDA,
     delay: &mut DELAY,
 ) -> Result<(), E> {
-    sda.set_low()?;
-    rst.set_low()?;
-    reset(rst, delay)
+    method1::<Eh1, _, _, _, _>(rst, sda, delay)
 }
 
 /// Reset the device and select I2C communication (method 1 including GPIO3)
@@ -27,20 +25,17 @@ pub fn reset_and_select_i2c_method1<
 /// to perform the reset.
 pub fn reset_and_select_i2c_method1_with_gpio3<
     E,
-    RST: OutputPin<Error = E>,
-    SDA: OutputPin<Error = E>,
-    GPIO3: OutputPin<Error = E>,
-    DELAY: DelayMs<u8>,
+    RST: embedded_hal::digital::OutputPin<Error = E>,
+    SDA: embedded_hal::digital::OutputPin<Error = E>,
+    GPIO3: embedded_hal::digital::OutputPin<Error = E>,
+    DELAY: embedded_hal::delay::DelayNs,
 >(
     rst: &mut RST,
     sda: &mut SDA,
     gpio3: &mut GPIO3,
     delay: &mut DELAY,
 ) -> Result<(), E> {
-    sda.set_low()?;
-    rst.set_low()?;
-    gpio3.set_low()?;
-    reset(rst, delay)
+    method1_with_gpio3::<Eh1, _, _, _, _, _>(rst, sda, gpio3, delay)
 }
 
 /// Reset the device and select I2C communication (method 2)
@@ -49,28 +44,144 @@ pub fn reset_and_select_i2c_method1_with_gpio3<
 /// to perform the reset.
 pub fn reset_and_select_i2c_method2<
     E,
-    RST: OutputPin<Error = E>,
-    GPIO1: OutputPin<Error = E>,
-    GPIO3: OutputPin<Error = E>,
-    DELAY: DelayMs<u8>,
+    RST: embedded_hal::digital::OutputPin<Error = E>,
+    GPIO1: embedded_hal::digital::OutputPin<Error = E>,
+    GPIO3: embedded_hal::digital::OutputPin<Error = E>,
+    DELAY: embedded_hal::delay::DelayNs,
 >(
     rst: &mut RST,
     gpio1: &mut GPIO1,
     gpio3: &mut GPIO3,
     delay: &mut DELAY,
 ) -> Result<(), E> {
-    rst.set_low()?;
-    gpio3.set_high()?;
-    gpio1.set_high()?;
-    reset(rst, delay)
+    method2::<Eh1, _, _, _, _, _>(rst, gpio1, gpio3, delay)
 }
 
-fn reset<E, RST: OutputPin<Error = E>, DELAY: DelayMs<u8>>(
+/// Pin driven during a reset sequence
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ResetPin {
+    Rst,
+    Sda,
+    Gpio1,
+    Gpio3,
+}
+
+/// Step of a reset sequence
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ResetStep {
+    /// Drive the pin high (`true`) or low (`false`)
+    Set(ResetPin, bool),
+    /// Wait for the given number of milliseconds
+    Wait(u32),
+}
+
+/// Reset and I2C selection method 1: SDA low, GPIO3 untouched
+pub(crate) const METHOD1: [ResetStep; 5] = [
+    ResetStep::Set(ResetPin::Sda, false),
+    ResetStep::Set(ResetPin::Rst, false),
+    ResetStep::Wait(1),
+    ResetStep::Set(ResetPin::Rst, true),
+    ResetStep::Wait(1),
+];
+
+/// Reset and I2C selection method 1: SDA and GPIO3 low
+pub(crate) const METHOD1_WITH_GPIO3: [ResetStep; 6] = [
+    ResetStep::Set(ResetPin::Sda, false),
+    ResetStep::Set(ResetPin::Rst, false),
+    ResetStep::Set(ResetPin::Gpio3, false),
+    ResetStep::Wait(1),
+    ResetStep::Set(ResetPin::Rst, true),
+    ResetStep::Wait(1),
+];
+
+/// Reset and I2C selection method 2: GPIO1 and GPIO3 high
+pub(crate) const METHOD2: [ResetStep; 6] = [
+    ResetStep::Set(ResetPin::Rst, false),
+    ResetStep::Set(ResetPin::Gpio3, true),
+    ResetStep::Set(ResetPin::Gpio1, true),
+    ResetStep::Wait(1),
+    ResetStep::Set(ResetPin::Rst, true),
+    ResetStep::Wait(1),
+];
+
+pub(crate) fn method1<
+    HAL,
+    E,
+    RST: OutputPin<HAL, Error = E>,
+    SDA: OutputPin<HAL, Error = E>,
+    DELAY: Delay<HAL>,
+>(
+    rst: &mut RST,
+    sda: &mut SDA,
+    delay: &mut DELAY,
+) -> Result<(), E> {
+    run(&METHOD1, delay, |pin, high| match pin {
+        ResetPin::Rst => set_level(rst, high),
+        ResetPin::Sda => set_level(sda, high),
+        ResetPin::Gpio1 | ResetPin::Gpio3 => Ok(()),
+    })
+}
+
+pub(crate) fn method1_with_gpio3<
+    HAL,
+    E,
+    RST: OutputPin<HAL, Error = E>,
+    SDA: OutputPin<HAL, Error = E>,
+    GPIO3: OutputPin<HAL, Error = E>,
+    DELAY: Delay<HAL>,
+>(
     rst: &mut RST,
+    sda: &mut SDA,
+    gpio3: &mut GPIO3,
     delay: &mut DELAY,
 ) -> Result<(), E> {
-    delay.delay_ms(1);
-    rst.set_high()?;
-    delay.delay_ms(1);
+    run(&METHOD1_WITH_GPIO3, delay, |pin, high| match pin {
+        ResetPin::Rst => set_level(rst, high),
+        ResetPin::Sda => set_level(sda, high),
+        ResetPin::Gpio3 => set_level(gpio3, high),
+        ResetPin::Gpio1 => Ok(()),
+    })
+}
+
+pub(crate) fn method2<
+    HAL,
+    E,
+    RST: OutputPin<HAL, Error = E>,
+    GPIO1: OutputPin<HAL, Error = E>,
+    GPIO3: OutputPin<HAL, Error = E>,
+    DELAY: Delay<HAL>,
+>(
+    rst: &mut RST,
+    gpio1: &mut GPIO1,
+    gpio3: &mut GPIO3,
+    delay: &mut DELAY,
+) -> Result<(), E> {
+    run(&METHOD2, delay, |pin, high| match pin {
+        ResetPin::Rst => set_level(rst, high),
+        ResetPin::Gpio1 => set_level(gpio1, high),
+        ResetPin::Gpio3 => set_level(gpio3, high),
+        ResetPin::Sda => Ok(()),
+    })
+}
+
+fn run<HAL, E, DELAY: Delay<HAL>>(
+    steps: &[ResetStep],
+    delay: &mut DELAY,
+    mut set: impl FnMut(ResetPin, bool) -> Result<(), E>,
+) -> Result<(), E> {
+    for step in steps {
+        match *step {
+            ResetStep::Set(pin, high) => set(pin, high)?,
+            ResetStep::Wait(ms) => delay.delay_ms(ms),
+        }
+    }
     Ok(())
 }
+
+fn set_level<HAL, P: OutputPin<HAL>>(pin: &mut P, high: bool) -> Result<(), P::Error> {
+    if high {
+        pin.set_high()
+    } else {
+        pin.set_low()
+    }
+}
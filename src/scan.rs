@@ -0,0 +1,213 @@
+use crate::{
+    fields::{self, STC_CLEAR_TIMEOUT_MS},
+    hal::{Delay, I2cBus},
+    Error, Frequency, Operation, ScanState, SeekDirection, SeekMode, SeekOutcome, Si4703, Station,
+};
+
+impl<I2C, E, IC, HAL> Si4703<I2C, IC, HAL>
+where
+    I2C: I2cBus<HAL, Error = E>,
+{
+    /// Scan the whole band for receivable stations
+    ///
+    /// The device is tuned to the bottom of the band and then seeks up
+    /// with `SeekMode::NoWrap` until the band limit is reached. Each valid
+    /// station found is stored in `stations` and `progress` is called with
+    /// the frequency reached after every seek. The lowest channel of the
+    /// band itself is not considered.
+    ///
+    /// Returns the number of stations stored. The scan ends early once
+    /// `stations` is full and does not start at all if it is empty.
+    ///
+    /// This must be called until it returns `Ok`, for example with
+    /// `nb::block!`, passing the same buffer every time. In the meantime,
+    /// seeking and tuning return `Error::Busy`. A scan that is not polled
+    /// to completion must be cancelled with
+    /// [`cancel_scan()`](#method.cancel_scan).
+    pub fn scan_band<F: FnMut(Frequency)>(
+        &mut self,
+        stations: &mut [Station],
+        mut progress: F,
+    ) -> nb::Result<usize, Error<E>> {
+        loop {
+            match self.scan_state {
+                ScanState::Idle => {
+                    if stations.is_empty() {
+                        return Ok(0);
+                    }
+                    if self.is_busy() {
+                        return Err(nb::Error::Other(Error::Busy));
+                    }
+                    self.scan_state = ScanState::Tuning;
+                }
+                ScanState::Tuning => {
+                    let start = |regs: &mut [u16; 16]| Ok(fields::start_tune(regs, 0));
+                    self.scan_step(|dev| dev.run_operation(Operation::Tune, &start))?;
+                    self.scan_state = ScanState::Seeking(0);
+                }
+                ScanState::Seeking(mut found) => {
+                    let start = |regs: &mut [u16; 16]| {
+                        Ok(fields::start_seek(
+                            regs,
+                            SeekMode::NoWrap,
+                            SeekDirection::Up,
+                        ))
+                    };
+                    let regs = self.scan_step(|dev| dev.run_operation(Operation::Seek, &start))?;
+                    let outcome = fields::seek_outcome(&regs);
+                    progress(outcome.frequency);
+                    if record_station(&outcome, stations, &mut found) {
+                        self.scan_state = ScanState::Idle;
+                        return Ok(found);
+                    }
+                    self.scan_state = ScanState::Seeking(found);
+                }
+            }
+        }
+    }
+
+    /// Scan the whole band polling the STC bit until the scan is finished
+    ///
+    /// The STC bit is read every 10ms using the provided delay.
+    /// See [`scan_band()`](#method.scan_band).
+    /// If the scan does not finish within `timeout_ms` milliseconds,
+    /// the ongoing seek or tune is cancelled and `Error::Timeout` is returned.
+    pub fn scan_band_blocking<D: Delay<HAL>, F: FnMut(Frequency)>(
+        &mut self,
+        stations: &mut [Station],
+        delay: &mut D,
+        timeout_ms: u32,
+        mut progress: F,
+    ) -> Result<usize, Error<E>> {
+        let result = self.poll_until_complete(delay, timeout_ms, |dev| {
+            dev.scan_band(stations, &mut progress)
+        });
+        if let Err(Error::Timeout) = result {
+            self.poll_until_complete(delay, STC_CLEAR_TIMEOUT_MS, Self::cancel_scan)?;
+        }
+        result
+    }
+
+    /// Cancel an ongoing band scan
+    ///
+    /// The scan is abandoned so that seeking and tuning are possible again
+    /// and the seek or tune in progress, if any, is cancelled. This must be
+    /// called until it returns `Ok`, for example with `nb::block!`.
+    pub fn cancel_scan(&mut self) -> nb::Result<(), Error<E>> {
+        self.scan_state = ScanState::Idle;
+        match self.current_operation() {
+            Some(operation) => self.cancel_operation(operation),
+            None => Ok(()),
+        }
+    }
+
+    /// Run a step of the scan, resetting the scan state on errors.
+    fn scan_step<T, F>(&mut self, step: F) -> nb::Result<T, Error<E>>
+    where
+        F: FnOnce(&mut Self) -> nb::Result<T, Error<E>>,
+    {
+        let result = step(self);
+        if let Err(nb::Error::Other(_)) = result {
+            self.scan_state = ScanState::Idle;
+        }
+        result
+    }
+}
+
+/// Store the station found by a seek of a band scan.
+///
+/// Returns whether the scan is finished.
+pub(crate) fn record_station(
+    outcome: &SeekOutcome,
+    stations: &mut [Station],
+    found: &mut usize,
+) -> bool {
+    if outcome.band_limit {
+        return true;
+    }
+    if outcome.found() {
+        if let Some(station) = stations.get_mut(*found) {
+            *station = Station::from(*outcome);
+            *found += 1;
+        }
+        return *found >= stations.len();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(khz: u32, afc_railed: bool, band_limit: bool) -> SeekOutcome {
+        SeekOutcome {
+            frequency: Frequency::from_khz(khz),
+            rssi: 30,
+            stereo: true,
+            afc_railed,
+            band_limit,
+        }
+    }
+
+    #[test]
+    fn records_valid_stations() {
+        let mut stations = [Station::default(); 2];
+        let mut found = 0;
+        assert!(!record_station(
+            &outcome(88_100, false, false),
+            &mut stations,
+            &mut found
+        ));
+        assert!(!record_station(
+            &outcome(89_100, true, false),
+            &mut stations,
+            &mut found
+        ));
+        assert_eq!(1, found);
+        assert_eq!(Frequency::from_khz(88_100), stations[0].frequency);
+        assert_eq!(30, stations[0].rssi);
+        assert!(stations[0].stereo);
+    }
+
+    #[test]
+    fn finishes_at_band_limit() {
+        let mut stations = [Station::default(); 2];
+        let mut found = 0;
+        assert!(record_station(
+            &outcome(108_000, false, true),
+            &mut stations,
+            &mut found
+        ));
+        assert_eq!(0, found);
+    }
+
+    #[test]
+    fn finishes_when_buffer_is_full() {
+        let mut stations = [Station::default(); 1];
+        let mut found = 0;
+        assert!(record_station(
+            &outcome(88_100, false, false),
+            &mut stations,
+            &mut found
+        ));
+        assert_eq!(1, found);
+        let mut found = 0;
+        assert!(record_station(
+            &outcome(88_100, false, false),
+            &mut [],
+            &mut found
+        ));
+    }
+
+    #[test]
+    fn finishes_when_buffer_is_shorter_than_found() {
+        let mut stations = [Station::default(); 1];
+        let mut found = 2;
+        assert!(record_station(
+            &outcome(88_100, false, false),
+            &mut stations,
+            &mut found
+        ));
+        assert_eq!(2, found);
+    }
+}
@@ -1,28 +1,18 @@
 use crate::{
-    BitFlags, Error, ErrorWithPin, OperationState, Register, SeekDirection, SeekFmImpulseThreshold,
-    SeekMode, SeekSnrThreshold, Si4703,
+    fields::{self, Registers, OPERATION_CHECK_POLLS, STC_CLEAR_TIMEOUT_MS, STC_POLL_INTERVAL_MS},
+    hal::{Delay, I2cBus, InputPin},
+    BitFlags, Error, ErrorWithPin, Operation, OperationState, Register, ScanState, SeekDirection,
+    SeekFmImpulseThreshold, SeekMode, SeekOutcome, SeekSnrThreshold, Si4703,
 };
-use embedded_hal::{blocking::i2c, digital::v2::InputPin};
 
-impl<I2C, E, IC> Si4703<I2C, IC>
+/// Sets the register values starting an operation and returns the index
+/// of the last register that needs to be written.
+pub(crate) type SetStartValue<'a, E> = &'a dyn Fn(&mut [u16; 16]) -> Result<usize, Error<E>>;
+
+impl<I2C, E, IC, HAL> Si4703<I2C, IC, HAL>
 where
-    I2C: i2c::Write<Error = E> + i2c::Read<Error = E>,
+    I2C: I2cBus<HAL, Error = E>,
 {
-    fn get_powercfg_for_seek_config(
-        powercfg: u16,
-        mode: SeekMode,
-        direction: SeekDirection,
-    ) -> u16 {
-        let powercfg = match mode {
-            SeekMode::Wrap => powercfg | BitFlags::SKMODE,
-            SeekMode::NoWrap => powercfg & !BitFlags::SKMODE,
-        };
-        match direction {
-            SeekDirection::Up => powercfg | BitFlags::SEEKUP,
-            SeekDirection::Down => powercfg & !BitFlags::SEEKUP,
-        }
-    }
-
     /// Configure seek RSSI, SNR and FM impulse detection thresholds
     pub fn configure_seek(
         &mut self,
@@ -30,59 +20,69 @@ where
         snr_threshold: SeekSnrThreshold,
         fm_impulse_threshold: SeekFmImpulseThreshold,
     ) -> Result<(), Error<E>> {
-        let snr_mask = match snr_threshold {
-            SeekSnrThreshold::Disabled => 0,
-            SeekSnrThreshold::Enabled(v) if v > 7 || v == 0 => return Err(Error::InvalidInputData),
-            SeekSnrThreshold::Enabled(v) => v << 4,
-        };
-        let cnt_mask = match fm_impulse_threshold {
-            SeekFmImpulseThreshold::Disabled => 0,
-            SeekFmImpulseThreshold::Enabled(v) if v > 15 || v == 0 => {
-                return Err(Error::InvalidInputData)
-            }
-            SeekFmImpulseThreshold::Enabled(v) => v,
-        };
-        let mut regs = self.read_registers()?;
-        regs[Register::SYSCONFIG2] &= 0xFF00;
-        regs[Register::SYSCONFIG2] |= u16::from(rssi_threshold) << 8;
-        regs[Register::SYSCONFIG3] &= 0xFF00;
-        regs[Register::SYSCONFIG3] |= u16::from(snr_mask | cnt_mask);
-        self.write_registers(&regs[..=Register::SYSCONFIG3])
+        let thresholds = fields::seek_thresholds_mask(snr_threshold, fm_impulse_threshold)?;
+        self.update_registers(|regs| fields::configure_seek(regs, rssi_threshold, thresholds))
     }
 
     /// Seek
     ///
     /// It is not recommended to call this again this while the seeking
     /// is not finished. It should be waited on the STC interrupt pin.
-    pub fn seek(&mut self, mode: SeekMode, direction: SeekDirection) -> nb::Result<(), Error<E>> {
-        let set_initial_value = |regs: &mut [u16; 16]| {
-            let powercfg = regs[Register::POWERCFG] | BitFlags::SEEK;
-            regs[Register::POWERCFG] =
-                Self::get_powercfg_for_seek_config(powercfg, mode, direction);
-            Ok(Register::POWERCFG)
-        };
-        let mut state = self.seeking_state;
-        let result = self.tune_seek(
-            Register::POWERCFG,
-            BitFlags::SEEK,
-            &mut state,
-            &set_initial_value,
-        );
-        self.seeking_state = state;
+    ///
+    /// Once the seek is finished, its outcome is returned. This includes
+    /// whether the band limit was reached without finding a channel.
+    pub fn seek(
+        &mut self,
+        mode: SeekMode,
+        direction: SeekDirection,
+    ) -> nb::Result<SeekOutcome, Error<E>> {
+        let set_initial_value =
+            |regs: &mut [u16; 16]| Ok(fields::start_seek(regs, mode, direction));
+        self.tune_seek(Operation::Seek, &set_initial_value)
+            .map(|regs| fields::seek_outcome(&regs))
+    }
+
+    /// Seek polling the STC bit until the seek is finished
+    ///
+    /// The STC bit is read every 10ms using the provided delay.
+    /// Returns the outcome of the seek.
+    /// If the seek does not finish within `timeout_ms` milliseconds,
+    /// it is cancelled and `Error::Timeout` is returned.
+    pub fn seek_blocking<D: Delay<HAL>>(
+        &mut self,
+        mode: SeekMode,
+        direction: SeekDirection,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<SeekOutcome, Error<E>> {
+        let result = self.poll_until_complete(delay, timeout_ms, |dev| dev.seek(mode, direction));
+        if let Err(Error::Timeout) = result {
+            self.poll_until_complete(delay, STC_CLEAR_TIMEOUT_MS, Self::cancel_seek)?;
+        }
         result
     }
 
+    /// Cancel an ongoing seek
+    ///
+    /// The SEEK bit is cleared and the seek state is reset once the
+    /// device has cleared the STC bit. This must be called until it
+    /// returns `Ok`, for example with `nb::block!`.
+    pub fn cancel_seek(&mut self) -> nb::Result<(), Error<E>> {
+        self.cancel_operation(Operation::Seek)
+    }
+
     /// Seek using GPIO2 as STC interrupt pin (recommended)
     ///
     /// This will configure GPIO2 as STC interrupt pin and enable
     /// STC interrupts if appropriate.
-    pub fn seek_with_stc_int_pin<PinE, P: InputPin<Error = PinE>>(
+    /// Once the seek is finished, its outcome is returned.
+    pub fn seek_with_stc_int_pin<PinE, P: InputPin<HAL, Error = PinE>>(
         &mut self,
         mode: SeekMode,
         direction: SeekDirection,
-        stc_int_pin: &P,
-    ) -> nb::Result<(), ErrorWithPin<E, PinE>> {
-        if self.seeking_state == OperationState::Busy
+        stc_int_pin: &mut P,
+    ) -> nb::Result<SeekOutcome, ErrorWithPin<E, PinE>> {
+        if self.operation_state == OperationState::Busy(Operation::Seek)
             && stc_int_pin
                 .is_high()
                 .map_err(ErrorWithPin::Pin)
@@ -91,69 +91,153 @@ where
             Err(nb::Error::WouldBlock)
         } else {
             let set_initial_value = |regs: &mut [u16; 16]| {
-                let powercfg = regs[Register::POWERCFG] | BitFlags::SEEK;
-                regs[Register::POWERCFG] =
-                    Self::get_powercfg_for_seek_config(powercfg, mode, direction);
-                let previous_sysconfig1 = regs[Register::SYSCONFIG1];
-                regs[Register::SYSCONFIG1] &= 0xFFF3;
-                regs[Register::SYSCONFIG1] |= 1 << 2;
-                regs[Register::SYSCONFIG1] |= BitFlags::STCIEN;
-                if previous_sysconfig1 != regs[Register::SYSCONFIG1] {
+                let last = fields::start_seek(regs, mode, direction);
+                if fields::enable_stc_int_pin(regs) {
                     Ok(Register::SYSCONFIG1)
                 } else {
-                    Ok(Register::POWERCFG)
+                    Ok(last)
                 }
             };
-            let mut state = self.seeking_state;
-            let result = self.tune_seek(
-                Register::POWERCFG,
-                BitFlags::SEEK,
-                &mut state,
-                &set_initial_value,
-            );
-            self.seeking_state = state;
-            result.map_err(|e| match e {
-                nb::Error::Other(e) => nb::Error::Other(e.into()),
-                nb::Error::WouldBlock => nb::Error::WouldBlock,
-            })
+            self.tune_seek(Operation::Seek, &set_initial_value)
+                .map(|regs| fields::seek_outcome(&regs))
+                .map_err(|e| match e {
+                    nb::Error::Other(e) => nb::Error::Other(e.into()),
+                    nb::Error::WouldBlock => nb::Error::WouldBlock,
+                })
         }
     }
 
+    /// Call `operation` until it completes, waiting the STC polling
+    /// interval in between.
+    ///
+    /// Returns `Error::Timeout` once more than `timeout_ms` have been waited.
+    pub(crate) fn poll_until_complete<D, T, F>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+        mut operation: F,
+    ) -> Result<T, Error<E>>
+    where
+        D: Delay<HAL>,
+        F: FnMut(&mut Self) -> nb::Result<T, Error<E>>,
+    {
+        let mut waited_ms = 0;
+        loop {
+            match operation(self) {
+                Ok(value) => return Ok(value),
+                Err(nb::Error::Other(e)) => return Err(e),
+                Err(nb::Error::WouldBlock) if waited_ms >= timeout_ms => {
+                    return Err(Error::Timeout)
+                }
+                Err(nb::Error::WouldBlock) => {
+                    delay.delay_ms(STC_POLL_INTERVAL_MS);
+                    waited_ms += STC_POLL_INTERVAL_MS;
+                }
+            }
+        }
+    }
+
+    /// Start or poll a seek or tune requested by the user.
+    ///
+    /// Returns `Error::Busy` while a band scan is ongoing.
     pub(crate) fn tune_seek(
         &mut self,
-        register: usize,
-        bitflag: u16,
-        state: &mut OperationState,
-        set_start_value: &dyn Fn(&mut [u16; 16]) -> Result<usize, Error<E>>,
-    ) -> nb::Result<(), Error<E>> {
-        let mut regs = self.read_registers()?;
+        operation: Operation,
+        set_start_value: SetStartValue<E>,
+    ) -> nb::Result<Registers, Error<E>> {
+        if self.scan_state != ScanState::Idle {
+            return Err(nb::Error::Other(Error::Busy));
+        }
+        self.run_operation(operation, set_start_value)
+    }
+
+    /// Start or poll a seek or tune.
+    pub(crate) fn run_operation(
+        &mut self,
+        operation: Operation,
+        set_start_value: SetStartValue<E>,
+    ) -> nb::Result<Registers, Error<E>> {
+        if matches!(self.operation_state.operation(), Some(op) if op != operation) {
+            return Err(nb::Error::Other(Error::Busy));
+        }
+        let (register, bitflag) = fields::operation_bit(operation);
+        // The SEEK/TUNE bit is only changed by the host unless the device is
+        // reset, so the cached value is used together with a short read of
+        // the status registers. While an operation is ongoing, the bit is
+        // read back from the device every few polls to notice if it was lost.
+        self.operation_polls = self.operation_polls.saturating_add(1);
+        let mut regs = if matches!(self.operation_state, OperationState::Busy(_))
+            && self.operation_polls >= OPERATION_CHECK_POLLS
+        {
+            self.operation_polls = 0;
+            self.read_operation_registers()?
+        } else {
+            self.read_status_registers()?
+        };
         let flag = (regs[register] & bitflag) != 0;
         let stc = (regs[Register::STATUSRSSI] & BitFlags::STC) != 0;
-        let failed = (regs[Register::STATUSRSSI] & BitFlags::SF_BL) != 0;
-        let afcrl = (regs[Register::STATUSRSSI] & BitFlags::AFCRL) != 0;
 
-        match (*state, flag, stc) {
+        match (self.operation_state, flag, stc) {
             (OperationState::Idle, false, false) => {
                 let register = set_start_value(&mut regs)?;
                 self.write_registers(&regs[..=register])?;
-                *state = OperationState::Busy;
+                self.operation_state = OperationState::Busy(operation);
+                self.operation_polls = 0;
                 Err(nb::Error::WouldBlock)
             }
-            (OperationState::Busy, true, true) => {
+            (_, true, true) => {
+                let completed = regs;
                 regs[register] &= !bitflag;
                 self.write_registers(&regs[..=register])?;
-                *state = OperationState::WaitingForStcToClear(!failed && !afcrl);
+                self.operation_state = OperationState::WaitingForStcToClear(operation, completed);
                 Err(nb::Error::WouldBlock)
             }
-            (OperationState::WaitingForStcToClear(success), false, false) => {
-                *state = OperationState::Idle;
-                if success {
-                    Ok(())
-                } else {
-                    Err(nb::Error::Other(Error::SeekFailed))
-                }
+            (_, true, false) => {
+                // The operation is in progress, even if it was not started
+                // by this driver.
+                self.operation_state = OperationState::Busy(operation);
+                Err(nb::Error::WouldBlock)
+            }
+            (OperationState::WaitingForStcToClear(_, completed), false, false) => {
+                self.operation_state = OperationState::Idle;
+                Ok(completed)
+            }
+            (OperationState::Busy(_), false, false) => {
+                // The device lost the operation, for example after a reset.
+                // The shadow copy is then outdated as well.
+                self.operation_state = OperationState::Idle;
+                self.shadow_valid = false;
+                Err(nb::Error::Other(Error::Aborted))
             }
-            (_, _, _) => Err(nb::Error::WouldBlock),
+            (OperationState::WaitingForStcToClear(..), false, true) => Err(nb::Error::WouldBlock),
+            (_, false, true) => {
+                // STC is set without an operation to complete. The registers
+                // are out of sync with the device.
+                self.read_all_registers()?;
+                self.operation_state = OperationState::Idle;
+                Err(nb::Error::Other(Error::Aborted))
+            }
+        }
+    }
+
+    /// Clear the operation bit and wait for the STC bit to clear.
+    pub(crate) fn cancel_operation(&mut self, operation: Operation) -> nb::Result<(), Error<E>> {
+        if matches!(self.operation_state.operation(), Some(op) if op != operation) {
+            return Err(nb::Error::Other(Error::Busy));
+        }
+        let (register, bitflag) = fields::operation_bit(operation);
+        // As in run_operation(), the SEEK/TUNE bit is taken from the shadow
+        // copy and only the status registers are read.
+        let mut regs = self.read_status_registers()?;
+        if (regs[register] & bitflag) != 0 {
+            regs[register] &= !bitflag;
+            self.write_registers(&regs[..=register])?;
+            return Err(nb::Error::WouldBlock);
+        }
+        if (regs[Register::STATUSRSSI] & BitFlags::STC) != 0 {
+            return Err(nb::Error::WouldBlock);
         }
+        self.operation_state = OperationState::Idle;
+        Ok(())
     }
 }
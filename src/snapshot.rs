@@ -0,0 +1,258 @@
+use crate::{
+    fields::{self, Registers, OSCILLATOR_STARTUP_MS, POWERUP_MS, TUNE_TIMEOUT_MS},
+    hal::{Delay, I2cBus},
+    registers::WritableRegister,
+    BitFlags, Error, OperationState, Register, ScanState, Si4703, TuneChannel,
+};
+
+/// Image of the device configuration
+///
+/// Contains the control registers POWERCFG to TEST1. The CHANNEL register
+/// holds the channel the device was tuned to.
+/// It can be obtained with [`snapshot()`](struct.Si4703.html#method.snapshot)
+/// and applied again with [`restore()`](struct.Si4703.html#method.restore),
+/// for example after the device has been reset or powered down.
+///
+/// The image can be stored in a compact form with [`to_bytes()`](#method.to_bytes)
+/// and loaded again with [`from_bytes()`](#method.from_bytes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot {
+    registers: [u16; 6],
+}
+
+impl Snapshot {
+    /// Size of the serialized snapshot in bytes
+    pub const SIZE: usize = 12;
+
+    /// Create a snapshot from the register values
+    ///
+    /// The channel is taken from READCHAN. Ongoing seek and tune
+    /// operations are not part of the snapshot.
+    pub(crate) fn from_registers(regs: &Registers) -> Self {
+        let mut registers = [0; 6];
+        registers.copy_from_slice(&regs[Register::POWERCFG..=Register::TEST1]);
+        registers[1] = regs[Register::READCHAN];
+        Snapshot::from_raw(registers)
+    }
+
+    fn from_raw(mut registers: [u16; 6]) -> Self {
+        registers[0] &= !BitFlags::SEEK;
+        registers[1] &= 0x3FF;
+        Snapshot { registers }
+    }
+
+    /// Raw channel the device was tuned to
+    pub fn channel(&self) -> u16 {
+        self.registers[1]
+    }
+
+    /// Get the value of a control register in the snapshot
+    pub fn get<R: WritableRegister>(&self) -> R {
+        R::from(self.registers[R::ADDRESS - Register::POWERCFG])
+    }
+
+    /// Serialize the snapshot
+    ///
+    /// The registers POWERCFG to TEST1 are stored in big endian order.
+    pub fn to_bytes(&self) -> [u8; Snapshot::SIZE] {
+        let mut bytes = [0; Snapshot::SIZE];
+        for (chunk, value) in bytes.chunks_mut(2).zip(self.registers.iter()) {
+            chunk[0] = (value >> 8) as u8;
+            chunk[1] = *value as u8;
+        }
+        bytes
+    }
+
+    /// Deserialize a snapshot created with [`to_bytes()`](#method.to_bytes)
+    pub fn from_bytes(bytes: &[u8; Snapshot::SIZE]) -> Self {
+        let mut registers = [0; 6];
+        for (value, chunk) in registers.iter_mut().zip(bytes.chunks(2)) {
+            *value = u16::from(chunk[0]) << 8 | u16::from(chunk[1]);
+        }
+        Snapshot::from_raw(registers)
+    }
+
+    /// Whether the device was enabled when the snapshot was taken
+    ///
+    /// A device disabled with both the ENABLE and DISABLE bits set counts
+    /// as disabled.
+    pub(crate) fn is_enabled(&self) -> bool {
+        let powercfg = self.registers[0];
+        (powercfg & BitFlags::ENABLE) != 0 && (powercfg & BitFlags::DISABLE) == 0
+    }
+
+    /// Write the snapshot into the register image given the current values.
+    ///
+    /// A disabled device is only powered down if it is currently enabled.
+    /// Returns the last register to write.
+    pub(crate) fn apply(&self, regs: &mut Registers) -> usize {
+        let enabled = fields::is_enabled(regs);
+        regs[Register::POWERCFG..=Register::TEST1].copy_from_slice(&self.registers);
+        if !self.is_enabled() && !enabled {
+            regs[Register::POWERCFG] &= !(BitFlags::ENABLE | BitFlags::DISABLE);
+        }
+        Register::TEST1
+    }
+
+    /// Plan the writes restoring the snapshot given the current values.
+    ///
+    /// This follows the powerup sequence: if the device is to be enabled
+    /// and the oscillator is not running yet, it is enabled first on its own.
+    pub(crate) fn restore_plan(&self, current: &Registers) -> RestorePlan {
+        let mut registers = *current;
+        let last = self.apply(&mut registers);
+        let enabled = self.is_enabled();
+        let oscillator = if enabled
+            && fields::is_oscillator_enabled(&registers)
+            && !fields::is_oscillator_enabled(current)
+        {
+            let mut oscillator_only = registers;
+            oscillator_only[Register::POWERCFG] &= !BitFlags::ENABLE;
+            Some(oscillator_only)
+        } else {
+            None
+        };
+        RestorePlan {
+            oscillator,
+            registers,
+            last,
+            wait_powerup: enabled && !fields::is_enabled(current),
+            channel: if enabled { Some(self.channel()) } else { None },
+        }
+    }
+}
+
+/// Writes and waits needed to restore a snapshot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RestorePlan {
+    /// Registers to write first, waiting for the oscillator afterwards
+    pub(crate) oscillator: Option<Registers>,
+    /// Registers to write
+    pub(crate) registers: Registers,
+    /// Last register to write
+    pub(crate) last: usize,
+    /// Whether to wait for the powerup after writing the registers
+    pub(crate) wait_powerup: bool,
+    /// Channel to tune to at the end
+    pub(crate) channel: Option<u16>,
+}
+
+impl<I2C, E, IC, HAL> Si4703<I2C, IC, HAL>
+where
+    I2C: I2cBus<HAL, Error = E>,
+{
+    /// Take a snapshot of the device configuration
+    ///
+    /// This reads all registers and stores the control registers
+    /// POWERCFG to TEST1 and the tuned channel.
+    pub fn snapshot(&mut self) -> Result<Snapshot, Error<E>> {
+        let regs = self.read_all_registers()?;
+        Ok(Snapshot::from_registers(&regs))
+    }
+
+    /// Restore a snapshot of the device configuration
+    ///
+    /// This is meant to be used after resetting or powering down the device.
+    /// The control registers are written following the powerup sequence:
+    /// if necessary, the oscillator is enabled first and the provided delay
+    /// is used to wait for it to stabilize. If the device was enabled when
+    /// the snapshot was taken, it is enabled again and tuned to the
+    /// snapshot channel, polling the STC bit every 10ms. If the tuning does
+    /// not finish within 200ms, it is cancelled and `Error::Timeout` is
+    /// returned.
+    ///
+    /// Any ongoing seek or tune operation is forgotten.
+    pub fn restore<D: Delay<HAL>>(
+        &mut self,
+        snapshot: &Snapshot,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        self.operation_state = OperationState::Idle;
+        self.scan_state = ScanState::Idle;
+        let plan = snapshot.restore_plan(&self.read_all_registers()?);
+        if let Some(oscillator) = plan.oscillator {
+            self.write_registers(&oscillator[..=plan.last])?;
+            delay.delay_ms(OSCILLATOR_STARTUP_MS);
+        }
+        self.write_registers(&plan.registers[..=plan.last])?;
+        if plan.wait_powerup {
+            delay.delay_ms(POWERUP_MS);
+        }
+        match plan.channel {
+            Some(channel) => self
+                .tune_blocking(TuneChannel::Raw(channel), delay, TUNE_TIMEOUT_MS)
+                .map(|_| ()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_convert_to_bytes_and_back() {
+        let mut regs = [0; 16];
+        regs[Register::POWERCFG] = 0x4001;
+        regs[Register::SYSCONFIG2] = 0x1F1F;
+        regs[Register::TEST1] = 0x8100;
+        regs[Register::READCHAN] = 0xC123;
+        let snapshot = Snapshot::from_registers(&regs);
+        let bytes = snapshot.to_bytes();
+        assert_eq!([0x40, 1, 1, 0x23, 0, 0, 0x1F, 0x1F, 0, 0, 0x81, 0], bytes);
+        assert_eq!(snapshot, Snapshot::from_bytes(&bytes));
+    }
+
+    #[test]
+    fn ongoing_operations_are_not_stored() {
+        let mut regs = [0; 16];
+        regs[Register::POWERCFG] = BitFlags::SEEK | BitFlags::ENABLE;
+        regs[Register::CHANNEL] = BitFlags::TUNE | 5;
+        regs[Register::READCHAN] = 7;
+        let snapshot = Snapshot::from_registers(&regs);
+        assert_eq!(BitFlags::ENABLE, snapshot.registers[0]);
+        assert_eq!(7, snapshot.registers[1]);
+        assert_eq!(7, snapshot.channel());
+    }
+
+    #[test]
+    fn disabled_device_is_not_enabled() {
+        let mut regs = [0; 16];
+        regs[Register::POWERCFG] = BitFlags::DISABLE | BitFlags::ENABLE;
+        let snapshot = Snapshot::from_registers(&regs);
+        assert!(!snapshot.is_enabled());
+        let mut current = [0; 16];
+        current[Register::POWERCFG] = BitFlags::ENABLE;
+        snapshot.apply(&mut current);
+        assert_eq!(
+            BitFlags::DISABLE | BitFlags::ENABLE,
+            current[Register::POWERCFG]
+        );
+        let mut current = [0; 16];
+        snapshot.apply(&mut current);
+        assert_eq!(0, current[Register::POWERCFG]);
+    }
+
+    #[test]
+    fn restore_plan_enables_oscillator_first() {
+        let mut regs = [0; 16];
+        regs[Register::POWERCFG] = BitFlags::ENABLE;
+        regs[Register::TEST1] = BitFlags::XOSCEN;
+        regs[Register::READCHAN] = 7;
+        let snapshot = Snapshot::from_registers(&regs);
+        let plan = snapshot.restore_plan(&[0; 16]);
+        let oscillator = plan.oscillator.unwrap();
+        assert_eq!(0, oscillator[Register::POWERCFG]);
+        assert_eq!(BitFlags::XOSCEN, oscillator[Register::TEST1]);
+        assert_eq!(BitFlags::ENABLE, plan.registers[Register::POWERCFG]);
+        assert_eq!(Register::TEST1, plan.last);
+        assert!(plan.wait_powerup);
+        assert_eq!(Some(7), plan.channel);
+
+        let plan = snapshot.restore_plan(&plan.registers);
+        assert_eq!(None, plan.oscillator);
+        assert!(!plan.wait_powerup);
+        assert_eq!(Some(7), plan.channel);
+    }
+}
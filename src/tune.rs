@@ -1,41 +1,102 @@
-use crate::{BitFlags, Error, ErrorWithPin, OperationState, Register, Si4703, TuneChannel};
-use embedded_hal::{blocking::i2c, digital::v2::InputPin};
+use crate::{
+    fields::{self, STC_CLEAR_TIMEOUT_MS},
+    hal::{Delay, I2cBus, InputPin},
+    Error, ErrorWithPin, Operation, OperationState, Register, Rounding, Si4703, TuneChannel,
+    TuneOutcome,
+};
 
-impl<I2C, E, IC> Si4703<I2C, IC>
+impl<I2C, E, IC, HAL> Si4703<I2C, IC, HAL>
 where
-    I2C: i2c::Write<Error = E> + i2c::Read<Error = E>,
+    I2C: I2cBus<HAL, Error = E>,
 {
     /// Tune to a certain frequency
     ///
+    /// Frequencies between channels select the channel below.
+    /// See [`tune_rounded()`](#method.tune_rounded).
+    /// Once the tuning is finished, its outcome is returned.
+    ///
     /// It is not recommended to call this again this while the tuning
     /// is not finished. It should be waited on the STC interrupt pin.
-    pub fn tune(&mut self, channel: TuneChannel) -> nb::Result<(), Error<E>> {
+    pub fn tune(&mut self, channel: TuneChannel) -> nb::Result<TuneOutcome, Error<E>> {
+        self.tune_rounded(channel, Rounding::Floor)
+    }
+
+    /// Tune to a certain frequency using the given rounding policy
+    ///
+    /// Once the tuning is finished, its outcome including the frequency
+    /// of the tuned channel is returned.
+    /// With `Rounding::Strict`, `Error::OffGrid` is returned if the
+    /// frequency is not on the configured channel spacing grid.
+    pub fn tune_rounded(
+        &mut self,
+        channel: TuneChannel,
+        rounding: Rounding,
+    ) -> nb::Result<TuneOutcome, Error<E>> {
         let set_initial_value = |regs: &mut [u16; 16]| {
-            let raw = get_raw_tune_channel(regs[Register::SYSCONFIG2], channel)?;
-            regs[Register::CHANNEL] = BitFlags::TUNE | raw;
-            Ok(Register::CHANNEL)
+            let raw = get_raw_tune_channel(regs[Register::SYSCONFIG2], channel, rounding)?;
+            Ok(fields::start_tune(regs, raw))
         };
-        let mut state = self.tuning_state;
-        let result = self.tune_seek(
-            Register::CHANNEL,
-            BitFlags::TUNE,
-            &mut state,
-            &set_initial_value,
-        );
-        self.tuning_state = state;
+        self.tune_seek(Operation::Tune, &set_initial_value)
+            .map(|regs| fields::tune_outcome(&regs))
+    }
+
+    /// Tune to a certain frequency polling the STC bit until the tuning
+    /// is finished
+    ///
+    /// The STC bit is read every 10ms using the provided delay.
+    /// Frequencies between channels select the channel below.
+    /// Returns the outcome of the tuning.
+    /// If the tuning does not finish within `timeout_ms` milliseconds,
+    /// it is cancelled and `Error::Timeout` is returned.
+    pub fn tune_blocking<D: Delay<HAL>>(
+        &mut self,
+        channel: TuneChannel,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<TuneOutcome, Error<E>> {
+        let result = self.poll_until_complete(delay, timeout_ms, |dev| {
+            dev.tune_rounded(channel, Rounding::Floor)
+        });
+        if let Err(Error::Timeout) = result {
+            self.poll_until_complete(delay, STC_CLEAR_TIMEOUT_MS, Self::cancel_tune)?;
+        }
         result
     }
 
+    /// Cancel an ongoing tuning
+    ///
+    /// The TUNE bit is cleared and the tuning state is reset once the
+    /// device has cleared the STC bit. This must be called until it
+    /// returns `Ok`, for example with `nb::block!`.
+    pub fn cancel_tune(&mut self) -> nb::Result<(), Error<E>> {
+        self.cancel_operation(Operation::Tune)
+    }
+
     /// Tune using GPIO2 as STC interrupt pin (recommended)
     ///
     /// This will configure GPIO2 as STC interrupt pin and enable
     /// STC interrupts if appropriate.
-    pub fn tune_with_stc_int_pin<PinE, P: InputPin<Error = PinE>>(
+    /// Frequencies between channels select the channel below.
+    /// Once the tuning is finished, its outcome is returned.
+    pub fn tune_with_stc_int_pin<PinE, P: InputPin<HAL, Error = PinE>>(
+        &mut self,
+        channel: TuneChannel,
+        stc_int_pin: &mut P,
+    ) -> nb::Result<TuneOutcome, ErrorWithPin<E, PinE>> {
+        self.tune_rounded_with_stc_int_pin(channel, Rounding::Floor, stc_int_pin)
+    }
+
+    /// Tune using GPIO2 as STC interrupt pin and the given rounding policy
+    ///
+    /// Once the tuning is finished, its outcome is returned.
+    /// See [`tune_rounded()`](#method.tune_rounded).
+    pub fn tune_rounded_with_stc_int_pin<PinE, P: InputPin<HAL, Error = PinE>>(
         &mut self,
         channel: TuneChannel,
-        stc_int_pin: &P,
-    ) -> nb::Result<(), ErrorWithPin<E, PinE>> {
-        if self.tuning_state == OperationState::Busy
+        rounding: Rounding,
+        stc_int_pin: &mut P,
+    ) -> nb::Result<TuneOutcome, ErrorWithPin<E, PinE>> {
+        if self.operation_state == OperationState::Busy(Operation::Tune)
             && stc_int_pin
                 .is_high()
                 .map_err(ErrorWithPin::Pin)
@@ -44,54 +105,37 @@ where
             Err(nb::Error::WouldBlock)
         } else {
             let set_initial_value = |regs: &mut [u16; 16]| {
-                let raw = get_raw_tune_channel(regs[Register::SYSCONFIG2], channel)?;
-                regs[Register::CHANNEL] = BitFlags::TUNE | raw;
-                let previous_sysconfig1 = regs[Register::SYSCONFIG1];
-                regs[Register::SYSCONFIG1] &= 0xFFF3;
-                regs[Register::SYSCONFIG1] |= 1 << 2;
-                regs[Register::SYSCONFIG1] |= BitFlags::STCIEN;
-                if previous_sysconfig1 != regs[Register::SYSCONFIG1] {
+                let raw = get_raw_tune_channel(regs[Register::SYSCONFIG2], channel, rounding)?;
+                let last = fields::start_tune(regs, raw);
+                if fields::enable_stc_int_pin(regs) {
                     Ok(Register::SYSCONFIG1)
                 } else {
-                    Ok(Register::CHANNEL)
+                    Ok(last)
                 }
             };
-            let mut state = self.tuning_state;
-            let result = self.tune_seek(
-                Register::CHANNEL,
-                BitFlags::TUNE,
-                &mut state,
-                &set_initial_value,
-            );
-            self.tuning_state = state;
-            result.map_err(|e| match e {
-                nb::Error::Other(e) => nb::Error::Other(e.into()),
-                nb::Error::WouldBlock => nb::Error::WouldBlock,
-            })
+            self.tune_seek(Operation::Tune, &set_initial_value)
+                .map(|regs| fields::tune_outcome(&regs))
+                .map_err(|e| match e {
+                    nb::Error::Other(e) => nb::Error::Other(e.into()),
+                    nb::Error::WouldBlock => nb::Error::WouldBlock,
+                })
         }
     }
 }
 
-fn get_raw_tune_channel<E>(sysconfig2: u16, channel: TuneChannel) -> Result<u16, Error<E>> {
+pub(crate) fn get_raw_tune_channel<E>(
+    sysconfig2: u16,
+    channel: TuneChannel,
+    rounding: Rounding,
+) -> Result<u16, Error<E>> {
     match channel {
         TuneChannel::Raw(raw) if raw >= (1 << 10) => Err(Error::InvalidInputData),
         TuneChannel::Raw(raw) => Ok(raw),
         TuneChannel::Mhz(mhz) => {
-            let (band_min, band_max) = match (sysconfig2 & (3 << 6)) >> 6 {
-                0 => (87.5, 108.0),
-                1 => (76.0, 108.0),
-                _ => (76.0, 90.0),
-            };
-            if mhz < band_min || mhz > band_max {
-                return Err(Error::InvalidInputData);
-            }
-            let spacing_mhz = match (sysconfig2 & (3 << 4)) >> 4 {
-                0 => 0.2,
-                1 => 0.1,
-                _ => 0.05,
-            };
-            Ok(libm::floorf((mhz - band_min) / spacing_mhz) as u16)
+            let khz = libm::roundf(mhz * 1000.0) as u32;
+            get_raw_tune_channel(sysconfig2, TuneChannel::Khz(khz), rounding)
         }
+        TuneChannel::Khz(khz) => fields::channel_plan(sysconfig2).round(khz, rounding),
     }
 }
 
@@ -103,7 +147,7 @@ mod tests {
         ($sysconfig2:expr, $channel:expr, $expected:expr) => {
             assert_eq!(
                 $expected,
-                get_raw_tune_channel::<()>($sysconfig2, $channel).unwrap()
+                get_raw_tune_channel::<()>($sysconfig2, $channel, Rounding::Floor).unwrap()
             );
         };
     }
@@ -116,7 +160,7 @@ mod tests {
 
     macro_rules! get_raw_tune_channel_error {
         ($sysconfig2:expr, $channel:expr) => {
-            match get_raw_tune_channel::<()>($sysconfig2, $channel) {
+            match get_raw_tune_channel::<()>($sysconfig2, $channel, Rounding::Floor) {
                 Err(Error::InvalidInputData) => (),
                 _ => panic!("Error not returned."),
             }
@@ -172,4 +216,66 @@ mod tests {
         get_raw_tune_channel_eq!(1 << 4, TuneChannel::Mhz(88.0), 5);
         get_raw_tune_channel_eq!(2 << 4, TuneChannel::Mhz(88.0), 10);
     }
+
+    #[test]
+    fn get_raw_tune_channel_mhz_is_exact() {
+        get_raw_tune_channel_eq!(2 << 4, TuneChannel::Mhz(88.05), 11);
+        get_raw_tune_channel_eq!(1 << 4, TuneChannel::Mhz(98.3), 108);
+    }
+
+    #[test]
+    fn get_raw_tune_channel_khz() {
+        get_raw_tune_channel_eq!(0, TuneChannel::Khz(87_500), 0);
+        get_raw_tune_channel_eq!(2 << 4, TuneChannel::Khz(88_050), 11);
+        get_raw_tune_channel_eq!(1 << 6 | 1 << 4, TuneChannel::Khz(108_000), 320);
+        get_raw_tune_channel_error!(0, TuneChannel::Khz(87_499));
+        get_raw_tune_channel_error!(2 << 6, TuneChannel::Khz(90_001));
+    }
+
+    fn rounded(sysconfig2: u16, khz: u32, rounding: Rounding) -> Result<u16, Error<()>> {
+        get_raw_tune_channel(sysconfig2, TuneChannel::Khz(khz), rounding)
+    }
+
+    #[test]
+    fn get_raw_tune_channel_floor() {
+        assert_eq!(107, rounded(1 << 4, 98_290, Rounding::Floor).unwrap());
+        assert_eq!(108, rounded(1 << 4, 98_300, Rounding::Floor).unwrap());
+    }
+
+    #[test]
+    fn get_raw_tune_channel_nearest() {
+        assert_eq!(108, rounded(1 << 4, 98_290, Rounding::Nearest).unwrap());
+        assert_eq!(108, rounded(1 << 4, 98_349, Rounding::Nearest).unwrap());
+        assert_eq!(109, rounded(1 << 4, 98_350, Rounding::Nearest).unwrap());
+        assert_eq!(102, rounded(0, 108_000, Rounding::Nearest).unwrap());
+        assert_eq!(
+            109,
+            get_raw_tune_channel::<()>(1 << 4, TuneChannel::Mhz(98.38), Rounding::Nearest).unwrap()
+        );
+    }
+
+    #[test]
+    fn get_raw_tune_channel_strict() {
+        assert_eq!(108, rounded(1 << 4, 98_300, Rounding::Strict).unwrap());
+        assert!(matches!(
+            rounded(1 << 4, 98_290, Rounding::Strict),
+            Err(Error::OffGrid)
+        ));
+        assert!(matches!(
+            rounded(0, 108_000, Rounding::Strict),
+            Err(Error::OffGrid)
+        ));
+        assert!(matches!(
+            rounded(0, 108_100, Rounding::Strict),
+            Err(Error::InvalidInputData)
+        ));
+    }
+
+    #[test]
+    fn get_raw_tune_channel_raw_ignores_rounding() {
+        assert_eq!(
+            5,
+            get_raw_tune_channel::<()>(0, TuneChannel::Raw(5), Rounding::Strict).unwrap()
+        );
+    }
 }
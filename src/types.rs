@@ -1,4 +1,5 @@
-use core::marker::PhantomData;
+use crate::hal::Eh1;
+use core::{convert::Infallible, marker::PhantomData};
 
 /// Errors in this crate
 #[derive(Debug)]
@@ -7,8 +8,17 @@ pub enum Error<E> {
     I2C(E),
     /// Invalid input data provided
     InvalidInputData,
-    /// Seek operation failed / Band limit reached
-    SeekFailed,
+    /// The device is not a supported Si470x device
+    UnsupportedDevice,
+    /// The frequency is not on the configured channel spacing grid
+    OffGrid,
+    /// The operation did not complete in time
+    Timeout,
+    /// The operation was aborted by the device, for example after a reset,
+    /// or the registers were found in an inconsistent state
+    Aborted,
+    /// Another seek or tune operation is in progress
+    Busy,
 }
 
 /// Errors for operations involving I2C communication as well
@@ -21,8 +31,17 @@ pub enum ErrorWithPin<CommE, PinE> {
     Pin(PinE),
     /// Invalid input data provided
     InvalidInputData,
-    /// Seek operation failed / Band limit reached
-    SeekFailed,
+    /// The device is not a supported Si470x device
+    UnsupportedDevice,
+    /// The frequency is not on the configured channel spacing grid
+    OffGrid,
+    /// The operation did not complete in time
+    Timeout,
+    /// The operation was aborted by the device, for example after a reset,
+    /// or the registers were found in an inconsistent state
+    Aborted,
+    /// Another seek or tune operation is in progress
+    Busy,
 }
 
 impl<CommE, PinE> From<Error<CommE>> for ErrorWithPin<CommE, PinE> {
@@ -30,17 +49,132 @@ impl<CommE, PinE> From<Error<CommE>> for ErrorWithPin<CommE, PinE> {
         match error {
             Error::I2C(e) => ErrorWithPin::I2C(e),
             Error::InvalidInputData => ErrorWithPin::InvalidInputData,
-            Error::SeekFailed => ErrorWithPin::SeekFailed,
+            Error::UnsupportedDevice => ErrorWithPin::UnsupportedDevice,
+            Error::OffGrid => ErrorWithPin::OffGrid,
+            Error::Timeout => ErrorWithPin::Timeout,
+            Error::Aborted => ErrorWithPin::Aborted,
+            Error::Busy => ErrorWithPin::Busy,
         }
     }
 }
 
+/// Part number as read from the DEVICEID register
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartNumber {
+    /// Si4700/01/02/03
+    Si470x,
+    /// Unknown part number
+    Unknown(u8),
+}
+
+/// Manufacturer as read from the DEVICEID register
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Manufacturer {
+    /// Silicon Laboratories
+    SiliconLabs,
+    /// Unknown manufacturer ID
+    Unknown(u16),
+}
+
+/// Chip revision as read from the CHIPID register
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Revision {
+    /// Revision C
+    C,
+    /// Unknown revision
+    Unknown(u8),
+}
+
+/// Device as read from the CHIPID register
+///
+/// The Si4702/03 only report their device after powerup. Before that,
+/// they report the same value as the Si4700/01.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceKind {
+    /// Si4700
+    Si4700,
+    /// Si4701 (with RDS)
+    Si4701,
+    /// Si4702
+    Si4702,
+    /// Si4703 (with RDS)
+    Si4703,
+    /// Unknown device
+    Unknown(u8),
+}
+
+impl DeviceKind {
+    /// Whether the device supports RDS/RBDS
+    pub fn has_rds(self) -> bool {
+        matches!(self, DeviceKind::Si4701 | DeviceKind::Si4703)
+    }
+
+    /// Whether the device supports the extended volume range
+    pub fn has_extended_volume(self) -> bool {
+        matches!(self, DeviceKind::Si4702 | DeviceKind::Si4703)
+    }
+}
+
+/// Chip identification
+///
+/// Decoded from the DEVICEID and CHIPID registers.
+/// See [`chip_info()`](struct.Si4703.html#method.chip_info).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChipInfo {
+    /// Part number
+    pub part_number: PartNumber,
+    /// Manufacturer
+    pub manufacturer: Manufacturer,
+    /// Chip revision
+    pub revision: Revision,
+    /// Device
+    pub device: DeviceKind,
+    /// Firmware version (0 before powerup)
+    pub firmware: u8,
+}
+
+impl ChipInfo {
+    /// Whether the part number and manufacturer match a Si470x device
+    pub fn is_si470x(&self) -> bool {
+        self.part_number == PartNumber::Si470x && self.manufacturer == Manufacturer::SiliconLabs
+    }
+}
+
+/// Errors during the device initialization
+///
+/// See [`init()`](struct.Si4703.html#method.init) and
+/// [`init_with_reset()`](struct.Si4703.html#method.init_with_reset).
+/// The pin error type is only used by `init_with_reset()`.
+#[derive(Debug)]
+pub enum InitError<E, PinE = Infallible> {
+    /// Error setting the reset or SDA pin during the reset
+    Reset(PinE),
+    /// Error reading the registers and enabling the oscillator
+    EnableOscillator(Error<E>),
+    /// Error enabling the device
+    Enable(Error<E>),
+    /// Error reading the device ID and chip ID
+    ReadId(Error<E>),
+    /// The device ID or chip ID do not match the expected device
+    UnexpectedDevice(ChipInfo),
+    /// Error applying the configuration
+    ApplyConfig(Error<E>),
+}
+
 /// IC markers
 #[doc(hidden)]
 pub mod ic {
+    /// Used for Si4700 devices
+    #[derive(Debug)]
+    pub struct Si4700(());
+    /// Used for Si4701 devices
+    #[derive(Debug)]
+    pub struct Si4701(());
     /// Used for Si4702 devices
+    #[derive(Debug)]
     pub struct Si4702(());
     /// Used for Si4703 devices
+    #[derive(Debug)]
     pub struct Si4703(());
 }
 
@@ -49,22 +183,72 @@ pub mod ic {
 pub mod marker {
     use super::super::private;
     pub trait WithRds: private::Sealed {}
+    pub trait WithAudioHighZ: private::Sealed {}
+}
+
+/// Settings changed by the power down sequence to be restored on power up
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct PowerDownState {
+    /// RDS and GPIO bits of SYSCONFIG1
+    pub(crate) sysconfig1: u16,
+    pub(crate) audio_high_z: bool,
+}
+
+/// Seek or tune operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// Seek
+    Seek,
+    /// Tune
+    Tune,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum OperationState {
     Idle,
-    Busy,
-    WaitingForStcToClear(bool),
+    Busy(Operation),
+    /// Contains the register values read when the operation completed.
+    WaitingForStcToClear(Operation, [u16; 16]),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ScanState {
+    Idle,
+    Tuning,
+    /// Contains the number of stations found so far.
+    Seeking(usize),
+}
+
+impl OperationState {
+    pub(crate) fn operation(self) -> Option<Operation> {
+        match self {
+            OperationState::Idle => None,
+            OperationState::Busy(operation) => Some(operation),
+            OperationState::WaitingForStcToClear(operation, _) => Some(operation),
+        }
+    }
 }
 
 /// Si4703 device driver
+///
+/// The driver keeps a shadow copy of the control registers (POWERCFG to
+/// TEST1) so that configuration changes do not need to read the device
+/// first. See [`resync()`](#method.resync).
+///
+/// `HAL` selects the `embedded-hal` version of the I²C bus, pins and
+/// delays. See the [`hal`](hal/index.html) module.
 #[derive(Debug)]
-pub struct Si4703<I2C, IC> {
+pub struct Si4703<I2C, IC, HAL = Eh1> {
     pub(crate) i2c: I2C,
-    pub(crate) seeking_state: OperationState,
-    pub(crate) tuning_state: OperationState,
+    pub(crate) shadow: [u16; 16],
+    pub(crate) shadow_valid: bool,
+    pub(crate) operation_state: OperationState,
+    /// Number of polls since the SEEK/TUNE bit was last read from the device
+    pub(crate) operation_polls: u8,
+    pub(crate) scan_state: ScanState,
+    pub(crate) power_down_state: Option<PowerDownState>,
     pub(crate) _ic: PhantomData<IC>,
+    pub(crate) _hal: PhantomData<HAL>,
 }
 
 /// Seek mode
@@ -112,6 +296,61 @@ impl Default for DeEmphasis {
     }
 }
 
+/// Regional preset of band, channel spacing and de-emphasis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    /// Europe: 87.5-108 MHz, 100 kHz, 50 us
+    Europe,
+    /// USA: 87.5-108 MHz, 200 kHz, 75 us
+    Usa,
+    /// Japan: 76-90 MHz, 100 kHz, 50 us
+    Japan,
+    /// Japan wide band: 76-108 MHz, 100 kHz, 50 us
+    JapanWide,
+    /// Australia: 87.5-108 MHz, 200 kHz, 50 us
+    Australia,
+    /// Italy: 87.5-108 MHz, 50 kHz, 50 us
+    Italy,
+}
+
+impl Region {
+    /// All regional presets
+    pub const ALL: [Region; 6] = [
+        Region::Europe,
+        Region::Usa,
+        Region::Japan,
+        Region::JapanWide,
+        Region::Australia,
+        Region::Italy,
+    ];
+
+    /// Band used in the region
+    pub fn band(self) -> Band {
+        match self {
+            Region::Japan => Band::Mhz76_90,
+            Region::JapanWide => Band::Mhz76_108,
+            _ => Band::Mhz875_108,
+        }
+    }
+
+    /// Channel spacing used in the region
+    pub fn channel_spacing(self) -> ChannelSpacing {
+        match self {
+            Region::Usa | Region::Australia => ChannelSpacing::Khz200,
+            Region::Italy => ChannelSpacing::Khz50,
+            _ => ChannelSpacing::Khz100,
+        }
+    }
+
+    /// De-emphasis used in the region
+    pub fn deemphasis(self) -> DeEmphasis {
+        match self {
+            Region::Usa => DeEmphasis::Us75,
+            _ => DeEmphasis::Us50,
+        }
+    }
+}
+
 /// GPIO1 configuration
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Gpio1Config {
@@ -251,6 +490,9 @@ impl Default for StereoToMonoBlendLevel {
 }
 
 /// Volume
+///
+/// The extended volume range is only available on the Si4702/03.
+/// Selecting it on the Si4700/01 is rejected with `Error::InvalidInputData`.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Volume {
     /// Mute (0 volume) (default)
@@ -405,8 +647,211 @@ pub enum TuneChannel {
     /// Raw value for the channel select (10 bits)
     Raw(u16),
     /// Target frequency in MHz. The raw value will be aproximated taking
-    /// the configured band and spacing into account
+    /// the configured band and spacing into account.
+    /// See [`Rounding`](enum.Rounding.html).
     Mhz(f32),
+    /// Target frequency in kHz. The raw value will be aproximated taking
+    /// the configured band and spacing into account.
+    /// See [`Rounding`](enum.Rounding.html).
+    Khz(u32),
+}
+
+/// Rounding of a frequency to the configured channel spacing grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Select the channel at or below the frequency (default)
+    Floor,
+    /// Select the channel closest to the frequency
+    Nearest,
+    /// Return `Error::OffGrid` if the frequency is not exactly on a channel
+    Strict,
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Rounding::Floor
+    }
+}
+
+impl From<Frequency> for TuneChannel {
+    fn from(frequency: Frequency) -> Self {
+        TuneChannel::Khz(frequency.khz())
+    }
+}
+
+/// Channel frequency
+///
+/// Stored in kHz so that no floating point operations are necessary.
+/// It is displayed in MHz with two decimals, for example `98.30 MHz`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Frequency(u32);
+
+impl Frequency {
+    /// Create a frequency from a value in kHz
+    pub const fn from_khz(khz: u32) -> Self {
+        Frequency(khz)
+    }
+
+    /// Frequency in kHz
+    pub const fn khz(self) -> u32 {
+        self.0
+    }
+
+    /// Frequency in MHz
+    pub fn mhz(self) -> f32 {
+        self.0 as f32 / 1000.0
+    }
+}
+
+impl core::fmt::Display for Frequency {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}.{:02} MHz", self.0 / 1000, self.0 % 1000 / 10)
+    }
+}
+
+/// Device configuration
+///
+/// The default value corresponds to the reset values in the datasheet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Config {
+    /// Band
+    pub band: Band,
+    /// Channel spacing
+    pub channel_spacing: ChannelSpacing,
+    /// De-emphasis
+    pub deemphasis: DeEmphasis,
+    /// Volume
+    pub volume: Volume,
+    /// Softmute rate and attenuation or `None` to disable softmute
+    pub softmute: Option<(SoftmuteRate, SoftmuteAttenuation)>,
+    /// Stereo to mono blend level
+    pub blend_level: StereoToMonoBlendLevel,
+    /// Seek RSSI threshold
+    pub seek_rssi_threshold: u8,
+    /// Seek SNR threshold
+    pub seek_snr_threshold: SeekSnrThreshold,
+    /// Seek FM impulse detection threshold
+    pub seek_fm_impulse_threshold: SeekFmImpulseThreshold,
+    /// GPIO1 configuration
+    pub gpio1: Gpio1Config,
+    /// GPIO2 configuration
+    pub gpio2: Gpio2Config,
+    /// GPIO3 configuration
+    pub gpio3: Gpio3Config,
+    /// Automatic gain control enabled
+    pub auto_gain_control: bool,
+    /// RDS mode
+    pub rds_mode: RdsMode,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            band: Band::default(),
+            channel_spacing: ChannelSpacing::default(),
+            deemphasis: DeEmphasis::default(),
+            volume: Volume::default(),
+            softmute: Some((SoftmuteRate::default(), SoftmuteAttenuation::default())),
+            blend_level: StereoToMonoBlendLevel::default(),
+            seek_rssi_threshold: 0,
+            seek_snr_threshold: SeekSnrThreshold::default(),
+            seek_fm_impulse_threshold: SeekFmImpulseThreshold::default(),
+            gpio1: Gpio1Config::default(),
+            gpio2: Gpio2Config::default(),
+            gpio3: Gpio3Config::default(),
+            auto_gain_control: true,
+            rds_mode: RdsMode::default(),
+        }
+    }
+}
+
+/// Outcome of a completed seek
+///
+/// Decoded from the same register read that completed the seek.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeekOutcome {
+    /// Frequency of the channel the seek stopped at
+    pub frequency: Frequency,
+    /// Received signal strength indicator in dBµV (RSSI)
+    pub rssi: u8,
+    /// Stereo indicator (ST)
+    pub stereo: bool,
+    /// AFC rail: the channel is invalid (AFCRL)
+    pub afc_railed: bool,
+    /// The seek failed or reached the band limit without finding a channel (SF/BL)
+    pub band_limit: bool,
+}
+
+impl SeekOutcome {
+    /// Whether a valid channel was found
+    pub fn found(&self) -> bool {
+        !self.band_limit && !self.afc_railed
+    }
+}
+
+/// Outcome of a completed tuning
+///
+/// Decoded from the same register read that completed the tuning.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TuneOutcome {
+    /// Frequency of the tuned channel
+    pub frequency: Frequency,
+    /// Received signal strength indicator in dBµV (RSSI)
+    pub rssi: u8,
+    /// Stereo indicator (ST)
+    pub stereo: bool,
+    /// AFC rail: the channel is invalid (AFCRL)
+    pub afc_railed: bool,
+}
+
+/// Station found by a band scan
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Station {
+    /// Frequency of the station
+    pub frequency: Frequency,
+    /// Received signal strength indicator in dBµV (RSSI)
+    pub rssi: u8,
+    /// Stereo indicator (ST)
+    pub stereo: bool,
+}
+
+impl From<SeekOutcome> for Station {
+    fn from(outcome: SeekOutcome) -> Self {
+        Station {
+            frequency: outcome.frequency,
+            rssi: outcome.rssi,
+            stereo: outcome.stereo,
+        }
+    }
+}
+
+/// Device status decoded from the STATUSRSSI and READCHAN registers
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Status {
+    /// A new RDS group is ready (RDSR)
+    pub rds_ready: bool,
+    /// Seek/tune complete (STC)
+    pub seek_tune_complete: bool,
+    /// Seek failed or band limit reached (SF/BL)
+    pub seek_failed_or_band_limit: bool,
+    /// AFC rail: the tuned channel is invalid (AFCRL)
+    pub afc_rail: bool,
+    /// RDS decoder synchronized (RDSS, only in RDS verbose mode)
+    pub rds_synchronized: bool,
+    /// Stereo indicator (ST)
+    pub stereo: bool,
+    /// Received signal strength indicator in dBµV (RSSI)
+    pub rssi: u8,
+    /// Raw value of the current channel (READCHAN)
+    pub channel: u16,
+    /// RDS block A errors (BLERA)
+    pub block_a_errors: RdsBlockErrors,
+    /// RDS block B errors (BLERB)
+    pub block_b_errors: RdsBlockErrors,
+    /// RDS block C errors (BLERC)
+    pub block_c_errors: RdsBlockErrors,
+    /// RDS block D errors (BLERD)
+    pub block_d_errors: RdsBlockErrors,
 }
 
 /// RDS block errors
@@ -496,4 +941,15 @@ mod tests {
     default_test!(default_snr, SeekSnrThreshold, Disabled);
     default_test!(default_fm_impulse, SeekFmImpulseThreshold, Disabled);
     default_test!(default_rds_block_err, RdsBlockErrors, None);
+    default_test!(default_rounding, Rounding, Floor);
+
+    #[test]
+    fn can_convert_frequency() {
+        assert_eq!(98_300, Frequency::from_khz(98_300).khz());
+        assert!((Frequency::from_khz(98_300).mhz() - 98.3).abs() < 0.001);
+        assert_eq!(
+            TuneChannel::Khz(88_050),
+            TuneChannel::from(Frequency::from_khz(88_050))
+        );
+    }
 }
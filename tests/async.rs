@@ -0,0 +1,646 @@
+#![cfg(feature = "async")]
+
+use embassy_futures::{block_on, poll_once};
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_mock::eh1::{
+    delay::{CheckedDelay, Transaction as DelayTrans},
+    digital::{Edge, Mock as PinMock, State as PinState, Transaction as PinTrans},
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
+};
+use si4703::{
+    asynch::{detect, DetectedDevice, Si4703},
+    ic, Config, Error, Frequency, OutputMode, Rounding, SeekDirection, SeekMode, SeekOutcome,
+    Snapshot, Station, TuneChannel, Volume,
+};
+
+mod common;
+use self::common::{BitFlags as BF, DEV_ADDR};
+
+fn new_si4703(transactions: &[I2cTrans]) -> Si4703<I2cMock, ic::Si4703> {
+    Si4703::new(I2cMock::new(transactions))
+}
+
+fn destroy<IC>(dev: Si4703<I2cMock, IC>) {
+    dev.destroy().done();
+}
+
+#[test]
+fn can_create_and_destroy() {
+    let dev = new_si4703(&[]);
+    destroy(dev);
+}
+
+#[test]
+fn registers_are_only_read_once() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(BF::DMUTE >> 8) as u8, 0]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![(BF::DMUTE >> 8) as u8, 0, 0, 0, 0, 0, 0, 5, 0, 0],
+        ),
+    ];
+    let mut dev = new_si4703(&transactions);
+    block_on(dev.unmute()).unwrap();
+    block_on(dev.set_volume(Volume::Dbfsm20)).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_get_channel() {
+    let mut regs = [0; 32];
+    regs[23] = 1 << 4; // 100 kHz spacing
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, regs.to_vec()),
+        I2cTrans::read(DEV_ADDR, vec![0, 0, 0, 5]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let channel = block_on(dev.channel()).unwrap();
+    assert!((channel - 88.0).abs() < 0.01);
+    destroy(dev);
+}
+
+#[test]
+fn can_get_channel_khz() {
+    let mut regs = [0; 32];
+    regs[23] = 2 << 4; // 50 kHz spacing
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, regs.to_vec()),
+        I2cTrans::read(DEV_ADDR, vec![0, 0, 0, 11]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let channel = block_on(dev.channel_khz()).unwrap();
+    assert_eq!(Frequency::from_khz(88_050), channel);
+    destroy(dev);
+}
+
+#[test]
+fn can_tune_polling() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, (BF::TUNE >> 8) as u8, BF::TUNE as u8 | 2],
+        ),
+        I2cTrans::read(DEV_ADDR, vec![0, 0, 0, 0]),
+        I2cTrans::read(DEV_ADDR, vec![(BF::STC >> 8) as u8, 0, 0, 2]),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 2]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[DelayTrans::async_delay_ms(10)]);
+    let outcome = block_on(dev.tune(TuneChannel::Raw(2), &mut delay)).unwrap();
+    assert_eq!(Frequency::from_khz(87_900), outcome.frequency);
+    assert!(!outcome.afc_railed);
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_tune_with_stc_int_pin() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                0,
+                0,
+                (BF::TUNE >> 8) as u8,
+                BF::TUNE as u8 | 2,
+                (BF::STCIEN >> 8) as u8,
+                1 << 2,
+            ],
+        ),
+        I2cTrans::read(DEV_ADDR, vec![0, 0, 0, 0]),
+        I2cTrans::read(DEV_ADDR, vec![(BF::STC >> 8) as u8, 0, 0, 2]),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 2]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut pin = PinMock::new(&[PinTrans::wait_for_edge(Edge::Falling)]);
+    let mut delay = CheckedDelay::new(&[]);
+    let outcome =
+        block_on(dev.tune_with_stc_int_pin(TuneChannel::Raw(2), &mut pin, &mut delay)).unwrap();
+    assert_eq!(Frequency::from_khz(87_900), outcome.frequency);
+    pin.done();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn tune_waits_for_stc_to_clear() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, (BF::TUNE >> 8) as u8, BF::TUNE as u8 | 2],
+        ),
+        I2cTrans::read(DEV_ADDR, vec![(BF::STC >> 8) as u8, 0, 0, 2]),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 2]),
+        I2cTrans::read(DEV_ADDR, vec![(BF::STC >> 8) as u8, 0]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[DelayTrans::async_delay_ms(10)]);
+    block_on(dev.tune(TuneChannel::Raw(2), &mut delay)).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn tune_times_out_if_stc_does_not_clear() {
+    let mut transactions = vec![
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, (BF::TUNE >> 8) as u8, BF::TUNE as u8 | 2],
+        ),
+        I2cTrans::read(DEV_ADDR, vec![(BF::STC >> 8) as u8, 0, 0, 2]),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 2]),
+    ];
+    transactions.extend(vec![
+        I2cTrans::read(DEV_ADDR, vec![(BF::STC >> 8) as u8, 0]);
+        11
+    ]);
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&vec![DelayTrans::async_delay_ms(10); 10]);
+    let result = block_on(dev.tune(TuneChannel::Raw(2), &mut delay));
+    assert!(matches!(result, Err(Error::Timeout)));
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn tune_is_cancelled_if_stc_is_not_set() {
+    let mut transactions = vec![
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, (BF::TUNE >> 8) as u8, BF::TUNE as u8 | 2],
+        ),
+    ];
+    transactions.extend(vec![I2cTrans::read(DEV_ADDR, vec![0, 0, 0, 0]); 21]);
+    transactions.push(I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 2]));
+    transactions.push(I2cTrans::read(DEV_ADDR, vec![0, 0]));
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&vec![DelayTrans::async_delay_ms(10); 20]);
+    let result = block_on(dev.tune(TuneChannel::Raw(2), &mut delay));
+    assert!(matches!(result, Err(Error::Timeout)));
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn seek_is_cancelled_if_stc_is_not_set() {
+    let mut transactions = vec![
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(BF::SEEK >> 8) as u8, 0]),
+    ];
+    transactions.extend(vec![I2cTrans::read(DEV_ADDR, vec![0, 0, 0, 0]); 4001]);
+    transactions.push(I2cTrans::write(DEV_ADDR, vec![0, 0]));
+    transactions.push(I2cTrans::read(DEV_ADDR, vec![0, 0]));
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&vec![DelayTrans::async_delay_ms(10); 4000]);
+    let result = block_on(dev.seek(SeekMode::NoWrap, SeekDirection::Down, &mut delay));
+    assert!(matches!(result, Err(Error::Timeout)));
+    delay.done();
+    destroy(dev);
+}
+
+/// Delay that never finishes
+struct PendingDelay;
+
+impl DelayNs for PendingDelay {
+    async fn delay_ns(&mut self, _ns: u32) {
+        core::future::pending().await
+    }
+}
+
+#[test]
+fn dropped_seek_is_cancelled_before_tuning() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(BF::SEEK >> 8) as u8, 0]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, (BF::TUNE >> 8) as u8, BF::TUNE as u8 | 2],
+        ),
+        I2cTrans::read(DEV_ADDR, vec![(BF::STC >> 8) as u8, 0, 0, 2]),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 2]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut pending = PendingDelay;
+    let seek = dev.seek(SeekMode::NoWrap, SeekDirection::Down, &mut pending);
+    assert!(poll_once(seek).is_pending());
+    let mut delay = CheckedDelay::new(&[]);
+    block_on(dev.tune(TuneChannel::Raw(2), &mut delay)).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn tune_invalid_channel_returns_error() {
+    let transactions = [I2cTrans::read(DEV_ADDR, [0; 32].to_vec())];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[]);
+    let result = block_on(dev.tune(TuneChannel::Raw(1 << 10), &mut delay));
+    assert!(matches!(result, Err(Error::InvalidInputData)));
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_tune_rounded() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, (BF::TUNE >> 8) as u8, BF::TUNE as u8 | 3],
+        ),
+        I2cTrans::read(DEV_ADDR, vec![(BF::STC >> 8) as u8, 0, 0, 3]),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 3]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[]);
+    let outcome =
+        block_on(dev.tune_rounded(TuneChannel::Khz(88_050), Rounding::Nearest, &mut delay));
+    assert_eq!(Frequency::from_khz(88_100), outcome.unwrap().frequency);
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn tune_off_grid_returns_error() {
+    let transactions = [I2cTrans::read(DEV_ADDR, [0; 32].to_vec())];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[]);
+    let result = block_on(dev.tune_rounded(TuneChannel::Khz(88_050), Rounding::Strict, &mut delay));
+    assert!(matches!(result, Err(Error::OffGrid)));
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_seek_polling() {
+    let seek = BF::SEEK | BF::SEEKUP | BF::SKMODE;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(seek >> 8) as u8, seek as u8]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0, 0, 0]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0, 0, 0]),
+        I2cTrans::read(DEV_ADDR, vec![((BF::STC | BF::ST) >> 8) as u8, 0x1F, 0, 5]),
+        I2cTrans::write(DEV_ADDR, vec![((seek & !BF::SEEK) >> 8) as u8, 0]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::async_delay_ms(10),
+        DelayTrans::async_delay_ms(10),
+    ]);
+    let outcome = block_on(dev.seek(SeekMode::Wrap, SeekDirection::Up, &mut delay)).unwrap();
+    assert_eq!(
+        SeekOutcome {
+            frequency: Frequency::from_khz(88_500),
+            rssi: 0x1F,
+            stereo: true,
+            afc_railed: false,
+            band_limit: false,
+        },
+        outcome
+    );
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_scan_band() {
+    let seek = BF::SEEK | BF::SEEKUP;
+    let mut transactions = vec![
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, (BF::TUNE >> 8) as u8, 0]),
+        I2cTrans::read(DEV_ADDR, vec![(BF::STC >> 8) as u8, 0, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0]),
+    ];
+    for (statusrssi, readchan) in [(BF::ST | 0x2A, 6), (BF::SF_BL, 102)] {
+        let statusrssi = BF::STC | statusrssi;
+        transactions.extend([
+            I2cTrans::write(DEV_ADDR, vec![(seek >> 8) as u8, 0]),
+            I2cTrans::read(
+                DEV_ADDR,
+                vec![(statusrssi >> 8) as u8, statusrssi as u8, 0, readchan],
+            ),
+            I2cTrans::write(DEV_ADDR, vec![(BF::SEEKUP >> 8) as u8, 0]),
+            I2cTrans::read(DEV_ADDR, vec![0, 0]),
+        ]);
+    }
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[]);
+    let mut stations = [Station::default(); 4];
+    let mut reached = Vec::new();
+    let count = block_on(dev.scan_band(&mut stations, &mut delay, |frequency| {
+        reached.push(frequency)
+    }))
+    .unwrap();
+    assert_eq!(1, count);
+    assert_eq!(
+        Station {
+            frequency: Frequency::from_khz(88_700),
+            rssi: 0x2A,
+            stereo: true,
+        },
+        stations[0]
+    );
+    assert_eq!(
+        vec![Frequency::from_khz(88_700), Frequency::from_khz(107_900)],
+        reached
+    );
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn scan_with_empty_buffer_does_nothing() {
+    let mut dev = new_si4703(&[]);
+    let mut delay = CheckedDelay::new(&[]);
+    let count = block_on(dev.scan_band(&mut [], &mut delay, |_| ())).unwrap();
+    assert_eq!(0, count);
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_cancel_seek() {
+    let mut seeking_data = [0; 32];
+    seeking_data[16] = (BF::SEEK >> 8) as u8;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, seeking_data.to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0]),
+        I2cTrans::read(DEV_ADDR, vec![(BF::STC >> 8) as u8, 0]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[DelayTrans::async_delay_ms(10)]);
+    block_on(dev.cancel_seek(&mut delay)).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn cancel_tune_times_out() {
+    let stc = vec![(BF::STC >> 8) as u8, 0];
+    let mut transactions = vec![
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0]),
+    ];
+    transactions.extend(vec![I2cTrans::read(DEV_ADDR, stc); 11]);
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&vec![DelayTrans::async_delay_ms(10); 10]);
+    let result = block_on(dev.cancel_tune(&mut delay));
+    assert!(matches!(result, Err(Error::Timeout)));
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn seek_with_stc_int_pin_can_reach_band_limit() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                (BF::SEEK >> 8) as u8,
+                0,
+                0,
+                0,
+                (BF::STCIEN >> 8) as u8,
+                1 << 2,
+            ],
+        ),
+        I2cTrans::read(DEV_ADDR, vec![0, 0, 0, 0]),
+        I2cTrans::read(DEV_ADDR, vec![((BF::STC | BF::SF_BL) >> 8) as u8, 0, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, 0]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut pin = PinMock::new(&[PinTrans::wait_for_edge(Edge::Falling)]);
+    let mut delay = CheckedDelay::new(&[]);
+    let result = block_on(dev.seek_with_stc_int_pin(
+        SeekMode::NoWrap,
+        SeekDirection::Down,
+        &mut pin,
+        &mut delay,
+    ));
+    let outcome = result.unwrap();
+    assert!(outcome.band_limit);
+    assert!(!outcome.found());
+    pin.done();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_get_output_mode_and_rssi() {
+    let transactions = [I2cTrans::read(DEV_ADDR, vec![(BF::ST >> 8) as u8, 0x2A])];
+    let mut dev = new_si4703(&transactions);
+    let result = block_on(dev.output_mode_and_rssi()).unwrap();
+    assert_eq!((OutputMode::Stereo, 0x2A), result);
+    destroy(dev);
+}
+
+#[test]
+fn can_modify() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(BF::DMUTE >> 8) as u8, 0, 0, 0, 0, 0, 0, 5]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    block_on(dev.modify(|m| {
+        m.set_volume(Volume::Dbfsm20).unmute();
+    }))
+    .unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_restore_snapshot() {
+    let snapshot = Snapshot::from_bytes(&[0x40, 1, 0, 5, 0, 0, 0, 0x1F, 0, 0, 0x80, 0]);
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 0, 0, 5, 0, 0, 0, 0x1F, 0, 0, 0x80, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 5, 0, 0, 0, 0x1F, 0, 0, 0x80, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0x80, 5]),
+        I2cTrans::read(DEV_ADDR, vec![(BF::STC >> 8) as u8, 0, 0, 5]),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 5]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::async_delay_ms(500),
+        DelayTrans::async_delay_ms(110),
+    ]);
+    block_on(dev.restore(&snapshot, &mut delay)).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn restore_times_out_if_tuning_does_not_finish() {
+    let snapshot = Snapshot::from_bytes(&[0x40, 1, 0, 5, 0, 0, 0, 0x1F, 0, 0, 0x80, 0]);
+    let mut running = [0; 32];
+    running[16] = 0x40;
+    running[17] = 1;
+    running[26] = 0x80;
+    let mut transactions = vec![
+        I2cTrans::read(DEV_ADDR, running.to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 5, 0, 0, 0, 0x1F, 0, 0, 0x80, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0x80, 5]),
+    ];
+    transactions.extend(vec![I2cTrans::read(DEV_ADDR, vec![0, 0, 0, 0]); 21]);
+    transactions.extend([
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 5]),
+        I2cTrans::read(DEV_ADDR, vec![0, 0]),
+    ]);
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&vec![DelayTrans::async_delay_ms(10); 20]);
+    let result = block_on(dev.restore(&snapshot, &mut delay));
+    assert!(matches!(result, Err(Error::Timeout)));
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_init() {
+    let mut powered_up = [0; 32];
+    powered_up[12] = 0x12;
+    powered_up[13] = 0x42;
+    powered_up[14] = 0x12;
+    powered_up[15] = 0x53;
+    powered_up[17] = BF::ENABLE as u8;
+    powered_up[26] = (BF::XOSCEN >> 8) as u8;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, BF::ENABLE as u8]),
+        I2cTrans::read(DEV_ADDR, powered_up.to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0x80, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::async_delay_ms(500),
+        DelayTrans::async_delay_ms(110),
+    ]);
+    block_on(dev.init(&mut delay, &Config::default())).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_init_with_reset() {
+    let mut powered_up = [0; 32];
+    powered_up[12] = 0x12;
+    powered_up[13] = 0x42;
+    powered_up[14] = 0x12;
+    powered_up[15] = 0x53;
+    powered_up[17] = BF::ENABLE as u8;
+    powered_up[26] = (BF::XOSCEN >> 8) as u8;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, BF::ENABLE as u8]),
+        I2cTrans::read(DEV_ADDR, powered_up.to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0x80, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut rst = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let mut sda = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::async_delay_ms(1),
+        DelayTrans::async_delay_ms(1),
+        DelayTrans::async_delay_ms(500),
+        DelayTrans::async_delay_ms(110),
+    ]);
+    block_on(dev.init_with_reset(&mut rst, &mut sda, &mut delay, &Config::default())).unwrap();
+    rst.done();
+    sda.done();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn init_with_reset_forgets_power_down_settings() {
+    let mut powered_up = [0; 32];
+    powered_up[12] = 0x12;
+    powered_up[13] = 0x42;
+    powered_up[14] = 0x12;
+    powered_up[15] = 0x53;
+    powered_up[17] = BF::ENABLE as u8;
+    powered_up[26] = (BF::XOSCEN >> 8) as u8;
+    let mut running = powered_up;
+    running[20] = (BF::RDS >> 8) as u8;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, running.to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 1, 0, 0, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, 0x41]),
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, BF::ENABLE as u8]),
+        I2cTrans::read(DEV_ADDR, powered_up.to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0x80, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, BF::ENABLE as u8]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    block_on(dev.power_down()).unwrap();
+    let mut rst = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let mut sda = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::async_delay_ms(1),
+        DelayTrans::async_delay_ms(1),
+        DelayTrans::async_delay_ms(500),
+        DelayTrans::async_delay_ms(110),
+        DelayTrans::async_delay_ms(110),
+    ]);
+    block_on(dev.init_with_reset(&mut rst, &mut sda, &mut delay, &Config::default())).unwrap();
+    block_on(dev.power_up(&mut delay)).unwrap();
+    rst.done();
+    sda.done();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_power_down_and_up() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, (BF::AHIZEN >> 8) as u8, 0],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![0, 0x41]),
+        I2cTrans::write(DEV_ADDR, vec![0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    block_on(dev.power_down_with_audio_high_z()).unwrap();
+    let mut delay = CheckedDelay::new(&[DelayTrans::async_delay_ms(110)]);
+    block_on(dev.power_up(&mut delay)).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_detect() {
+    let mut data = [0; 32];
+    data[12] = 0x12;
+    data[13] = 0x42;
+    data[14] = 0x10;
+    data[15] = 0x53;
+    let transactions = [I2cTrans::read(DEV_ADDR, data.to_vec())];
+    let dev = block_on(detect(I2cMock::new(&transactions))).unwrap();
+    assert!(matches!(dev, DetectedDevice::Si4702(_)));
+    dev.destroy().done();
+}
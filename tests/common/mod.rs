@@ -1,8 +1,19 @@
-use embedded_hal_mock::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTrans};
 use si4703::{ic, Si4703};
 
 pub const DEV_ADDR: u8 = 0x10;
 
+pub struct Register;
+#[allow(unused)]
+impl Register {
+    pub const POWERCFG: usize = 0x2;
+    pub const CHANNEL: usize = 0x3;
+    pub const SYSCONFIG1: usize = 0x4;
+    pub const SYSCONFIG2: usize = 0x5;
+    pub const SYSCONFIG3: usize = 0x6;
+    pub const TEST1: usize = 0x7;
+}
+
 pub struct BitFlags;
 #[allow(unused)]
 impl BitFlags {
@@ -45,6 +56,7 @@ pub fn new_si4703(transactions: &[I2cTrans]) -> Si4703<I2cMock, ic::Si4703> {
     Si4703::new(I2cMock::new(transactions))
 }
 
+#[allow(unused)]
 pub fn destroy<IC>(dev: Si4703<I2cMock, IC>) {
     dev.destroy().done();
 }
@@ -109,7 +121,7 @@ macro_rules! write_test {
 #[macro_export]
 macro_rules! write_powercfg_test {
     ($name:ident, $value:expr, $method:ident $(, $arg:expr)*) => {
-        write_test!($name, $value, 9, 1, $method $(, $arg)*);
+        write_test!($name, $value, 16, 1, $method $(, $arg)*);
     };
 }
 
@@ -129,3 +141,21 @@ macro_rules! read_test {
         }
     };
 }
+
+#[macro_export]
+macro_rules! get_test {
+    ($name:ident, $register:expr, $value:expr, $expected:expr, $method:ident) => {
+        #[test]
+        fn $name() {
+            // reading starts at register 0x0A
+            let index = (($register + 16 - 0xA) % 16) * 2;
+            let mut data = [0; 32];
+            data[index] = ($value >> 8) as u8;
+            data[index + 1] = $value as u8;
+            let transactions = [I2cTrans::read(DEV_ADDR, data.to_vec())];
+            let mut dev = new_si4703(&transactions);
+            assert_eq!($expected, dev.$method().unwrap());
+            destroy(dev);
+        }
+    };
+}
@@ -0,0 +1,123 @@
+use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+use si4703::{
+    Band, ChannelSpacing, Config, DeEmphasis, Error, Gpio1Config, Gpio2Config, Gpio3Config,
+    RdsMode, SeekFmImpulseThreshold as Cnt, SeekSnrThreshold as Snr, Si4703, SoftmuteAttenuation,
+    SoftmuteRate, StereoToMonoBlendLevel as Blend, Volume,
+};
+
+mod common;
+use self::common::{destroy, new_si4703, BitFlags as BF, DEV_ADDR};
+
+fn custom_config() -> Config {
+    Config {
+        band: Band::Mhz76_90,
+        channel_spacing: ChannelSpacing::Khz100,
+        deemphasis: DeEmphasis::Us50,
+        volume: Volume::Dbfsm58,
+        softmute: Some((SoftmuteRate::Slow, SoftmuteAttenuation::Db10)),
+        blend_level: Blend::Dbuv25_43,
+        seek_rssi_threshold: 0x19,
+        seek_snr_threshold: Snr::Enabled(4),
+        seek_fm_impulse_threshold: Cnt::Enabled(8),
+        gpio1: Gpio1Config::High,
+        gpio2: Gpio2Config::StcRdsInterrupt,
+        gpio3: Gpio3Config::MonoStereoIndicator,
+        auto_gain_control: false,
+        rds_mode: RdsMode::Verbose,
+    }
+}
+
+const CUSTOM_SYSCONFIG1: u16 = BF::DE | BF::AGCD | 3 << 6 | 1 << 4 | 1 << 2 | 3;
+const CUSTOM_SYSCONFIG2: u16 = 0x1900 | 2 << 6 | 1 << 4 | 1;
+const CUSTOM_SYSCONFIG3: u16 = 2 << 14 | 3 << 12 | BF::VOLEXT | 4 << 4 | 8;
+
+#[test]
+fn default_matches_reset_values() {
+    let transactions = [I2cTrans::read(DEV_ADDR, [0; 32].to_vec())];
+    let mut dev = new_si4703(&transactions);
+    assert_eq!(Config::default(), dev.read_config().unwrap());
+    destroy(dev);
+}
+
+#[test]
+fn can_apply_default_config() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, [0; 12].to_vec()),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.apply_config(&Config::default()).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_apply_config() {
+    let mut data = [0; 32];
+    // enabled, unmuted, softmute disabled and oscillator enabled
+    let powercfg = BF::DSMUTE | BF::DMUTE | BF::ENABLE;
+    data[16] = (powercfg >> 8) as u8;
+    data[17] = powercfg as u8;
+    data[26] = (BF::XOSCEN >> 8) as u8;
+    let powercfg = BF::DMUTE | BF::RDSM | BF::ENABLE;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, data.to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                (powercfg >> 8) as u8,
+                powercfg as u8,
+                0,
+                0,
+                (CUSTOM_SYSCONFIG1 >> 8) as u8,
+                CUSTOM_SYSCONFIG1 as u8,
+                (CUSTOM_SYSCONFIG2 >> 8) as u8,
+                CUSTOM_SYSCONFIG2 as u8,
+                (CUSTOM_SYSCONFIG3 >> 8) as u8,
+                CUSTOM_SYSCONFIG3 as u8,
+                (BF::XOSCEN >> 8) as u8,
+                0,
+            ],
+        ),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.apply_config(&custom_config()).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_read_config() {
+    let mut data = [0; 32];
+    data[16] = (BF::RDSM >> 8) as u8;
+    data[20] = (CUSTOM_SYSCONFIG1 >> 8) as u8;
+    data[21] = CUSTOM_SYSCONFIG1 as u8;
+    data[22] = (CUSTOM_SYSCONFIG2 >> 8) as u8;
+    data[23] = CUSTOM_SYSCONFIG2 as u8;
+    data[24] = (CUSTOM_SYSCONFIG3 >> 8) as u8;
+    data[25] = CUSTOM_SYSCONFIG3 as u8;
+    let transactions = [I2cTrans::read(DEV_ADDR, data.to_vec())];
+    let mut dev = new_si4703(&transactions);
+    assert_eq!(custom_config(), dev.read_config().unwrap());
+    destroy(dev);
+}
+
+#[test]
+fn invalid_config_is_rejected() {
+    let mut dev = new_si4703(&[]);
+    let config = Config {
+        seek_snr_threshold: Snr::Enabled(8),
+        ..Config::default()
+    };
+    assert_error!(dev.apply_config(&config), Error::InvalidInputData);
+    destroy(dev);
+}
+
+#[test]
+fn extended_volume_config_is_rejected_on_si4701() {
+    let mut dev = Si4703::new_si4701(I2cMock::new(&[]));
+    let config = Config {
+        volume: Volume::Dbfsm58,
+        ..Config::default()
+    };
+    assert_error!(dev.apply_config(&config), Error::InvalidInputData);
+    destroy(dev);
+}
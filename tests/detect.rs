@@ -0,0 +1,103 @@
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+use si4703::{
+    detect, ChipInfo, DetectedDevice, DeviceKind, Error, Manufacturer, PartNumber, Revision,
+};
+
+mod common;
+use self::common::{destroy, new_si4703, DEV_ADDR};
+
+fn id_registers(device_id: u16, chip_id: u16) -> Vec<u8> {
+    let mut data = [0; 32];
+    data[12] = (device_id >> 8) as u8;
+    data[13] = device_id as u8;
+    data[14] = (chip_id >> 8) as u8;
+    data[15] = chip_id as u8;
+    data.to_vec()
+}
+
+fn detect_with(device_id: u16, chip_id: u16) -> Result<DetectedDevice<I2cMock>, Error<ErrorKind>> {
+    let transactions = [I2cTrans::read(DEV_ADDR, id_registers(device_id, chip_id))];
+    let mut i2c = I2cMock::new(&transactions);
+    let result = detect(i2c.clone());
+    i2c.done();
+    result
+}
+
+#[test]
+fn can_read_chip_info() {
+    let transactions = [I2cTrans::read(DEV_ADDR, id_registers(0x1242, 0x1253))];
+    let mut dev = new_si4703(&transactions);
+    let expected = ChipInfo {
+        part_number: PartNumber::Si470x,
+        manufacturer: Manufacturer::SiliconLabs,
+        revision: Revision::C,
+        device: DeviceKind::Si4703,
+        firmware: 0x13,
+    };
+    assert_eq!(expected, dev.chip_info().unwrap());
+    assert!(expected.is_si470x());
+    destroy(dev);
+}
+
+#[test]
+fn can_read_unknown_chip_info() {
+    let transactions = [I2cTrans::read(DEV_ADDR, id_registers(0x3123, 0x0880))];
+    let mut dev = new_si4703(&transactions);
+    let info = dev.chip_info().unwrap();
+    assert_eq!(PartNumber::Unknown(3), info.part_number);
+    assert_eq!(Manufacturer::Unknown(0x123), info.manufacturer);
+    assert_eq!(Revision::Unknown(2), info.revision);
+    assert_eq!(DeviceKind::Unknown(2), info.device);
+    assert!(!info.is_si470x());
+    destroy(dev);
+}
+
+#[test]
+fn rds_support() {
+    assert!(!DeviceKind::Si4700.has_rds());
+    assert!(DeviceKind::Si4701.has_rds());
+    assert!(!DeviceKind::Si4702.has_rds());
+    assert!(DeviceKind::Si4703.has_rds());
+    assert!(!DeviceKind::Unknown(0xF).has_rds());
+}
+
+#[test]
+fn detects_si4703() {
+    let dev = detect_with(0x1242, 0x1253).unwrap();
+    assert!(matches!(dev, DetectedDevice::Si4703(_)));
+}
+
+#[test]
+fn detects_si4702() {
+    let dev = detect_with(0x1242, 0x1053).unwrap();
+    assert!(matches!(dev, DetectedDevice::Si4702(_)));
+}
+
+#[test]
+fn detects_rds_support_before_powerup() {
+    let dev = detect_with(0x1242, 0x1200).unwrap();
+    assert!(matches!(dev, DetectedDevice::Si4703(_)));
+    let dev = detect_with(0x1242, 0x1000).unwrap();
+    assert!(matches!(dev, DetectedDevice::Si4702(_)));
+}
+
+#[test]
+fn detects_si4700_and_si4701_after_powerup() {
+    let dev = detect_with(0x1242, 0x1013).unwrap();
+    assert!(matches!(dev, DetectedDevice::Si4700(_)));
+    let dev = detect_with(0x1242, 0x1213).unwrap();
+    assert!(matches!(dev, DetectedDevice::Si4701(_)));
+}
+
+#[test]
+fn rejects_unknown_device() {
+    assert!(matches!(
+        detect_with(0xFFFF, 0xFFFF),
+        Err(Error::UnsupportedDevice)
+    ));
+    assert!(matches!(
+        detect_with(0x1242, 0x1080),
+        Err(Error::UnsupportedDevice)
+    ));
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "eh02")]
+use embedded_hal_mock::eh0::{
+    delay::NoopDelay,
+    digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
+};
+use nb::block;
+use si4703::{eh02, DetectedDevice, SeekDirection, SeekMode, Si4703};
+
+mod common;
+use self::common::{BitFlags as BF, DEV_ADDR};
+
+#[test]
+fn can_use_eh02_i2c() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, BF::ENABLE as u8]),
+    ];
+    let mut dev = Si4703::new_eh02(I2cMock::new(&transactions));
+    dev.enable().unwrap();
+    dev.destroy().done();
+}
+
+#[test]
+fn can_reset_with_eh02_pins() {
+    let mut rst = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let mut sda = PinMock::new(&[PinTrans::set(PinState::Low)]);
+    let mut delay = NoopDelay::new();
+    eh02::reset_and_select_i2c_method1(&mut rst, &mut sda, &mut delay).unwrap();
+    rst.done();
+    sda.done();
+}
+
+#[test]
+fn can_seek_with_eh02_stc_int_pin() {
+    let mut found_data = [0; 32];
+    found_data[0] = (BF::STC >> 8) as u8;
+    found_data[1] = BF::STC as u8;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                (BF::SEEK >> 8) as u8,
+                BF::SEEK as u8,
+                0,
+                0,
+                (BF::STCIEN >> 8) as u8,
+                BF::STCIEN as u8 | 1 << 2,
+            ],
+        ),
+        I2cTrans::read(DEV_ADDR, found_data[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0]),
+        I2cTrans::read(DEV_ADDR, [0; 32][..4].to_vec()),
+    ];
+    let mut pin = PinMock::new(&[PinTrans::get(PinState::High), PinTrans::get(PinState::Low)]);
+    let mut dev = Si4703::new_eh02(I2cMock::new(&transactions));
+    let outcome =
+        block!(dev.seek_with_stc_int_pin(SeekMode::NoWrap, SeekDirection::Down, &mut pin)).unwrap();
+    assert!(outcome.found());
+    dev.destroy().done();
+    pin.done();
+}
+
+#[test]
+fn can_detect_with_eh02_i2c() {
+    let mut data = [0; 32];
+    data[12] = 0x12;
+    data[13] = 0x42;
+    data[14] = 0x12;
+    data[15] = 0x53;
+    let transactions = [I2cTrans::read(DEV_ADDR, data.to_vec())];
+    match eh02::detect(I2cMock::new(&transactions)).unwrap() {
+        DetectedDevice::Si4703(dev) => dev.destroy().done(),
+        _ => panic!("Wrong device detected."),
+    }
+}
@@ -0,0 +1,186 @@
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::{
+    delay::{CheckedDelay, Transaction as DelayTrans},
+    digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
+    MockError,
+};
+use si4703::{
+    ChipInfo, Config, DeviceKind, Error, InitError, SeekSnrThreshold as Snr, Si4703, Volume,
+};
+
+mod common;
+use self::common::{destroy, new_si4703, BitFlags as BF, DEV_ADDR};
+
+fn powered_up(chip_id: u16) -> Vec<u8> {
+    let mut data = [0; 32];
+    data[12] = 0x12;
+    data[13] = 0x42;
+    data[14] = (chip_id >> 8) as u8;
+    data[15] = chip_id as u8;
+    data[17] = BF::ENABLE as u8;
+    data[26] = (BF::XOSCEN >> 8) as u8;
+    data.to_vec()
+}
+
+fn powerup_delay() -> CheckedDelay {
+    CheckedDelay::new(&[
+        DelayTrans::blocking_delay_ms(500),
+        DelayTrans::blocking_delay_ms(110),
+    ])
+}
+
+#[test]
+fn can_init() {
+    let config = Config {
+        volume: Volume::Dbfsm28,
+        ..Config::default()
+    };
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, BF::ENABLE as u8]),
+        I2cTrans::read(DEV_ADDR, powered_up(0x1253)),
+        I2cTrans::write(DEV_ADDR, vec![0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0x80, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = powerup_delay();
+    dev.init(&mut delay, &config).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_init_with_reset() {
+    let config = Config {
+        volume: Volume::Dbfsm28,
+        ..Config::default()
+    };
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, BF::ENABLE as u8]),
+        I2cTrans::read(DEV_ADDR, powered_up(0x1253)),
+        I2cTrans::write(DEV_ADDR, vec![0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0x80, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut rst = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let mut sda = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::blocking_delay_ms(1),
+        DelayTrans::blocking_delay_ms(1),
+        DelayTrans::blocking_delay_ms(500),
+        DelayTrans::blocking_delay_ms(110),
+    ]);
+    dev.init_with_reset(&mut rst, &mut sda, &mut delay, &config)
+        .unwrap();
+    rst.done();
+    sda.done();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn init_with_reset_reports_pin_error() {
+    let mut dev = new_si4703(&[]);
+    let mut rst = PinMock::new(&[]);
+    let mut sda = PinMock::new(&[
+        PinTrans::set(PinState::Low).with_error(MockError::Io(std::io::ErrorKind::Other))
+    ]);
+    let mut delay = CheckedDelay::new(&[]);
+    let result = dev.init_with_reset(&mut rst, &mut sda, &mut delay, &Config::default());
+    assert!(matches!(result, Err(InitError::Reset(_))));
+    rst.done();
+    sda.done();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn init_with_reset_forgets_power_down_settings() {
+    let mut running = powered_up(0x1253);
+    running[20] = (BF::RDS >> 8) as u8;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, running),
+        I2cTrans::write(DEV_ADDR, vec![0, 1, 0, 0, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, 0x41]),
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, BF::ENABLE as u8]),
+        I2cTrans::read(DEV_ADDR, powered_up(0x1253)),
+        I2cTrans::write(DEV_ADDR, vec![0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0x80, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, BF::ENABLE as u8]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.power_down().unwrap();
+    let mut rst = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let mut sda = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::blocking_delay_ms(1),
+        DelayTrans::blocking_delay_ms(1),
+        DelayTrans::blocking_delay_ms(500),
+        DelayTrans::blocking_delay_ms(110),
+        DelayTrans::blocking_delay_ms(110),
+    ]);
+    dev.init_with_reset(&mut rst, &mut sda, &mut delay, &Config::default())
+        .unwrap();
+    dev.power_up(&mut delay).unwrap();
+    rst.done();
+    sda.done();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn init_rejects_unexpected_device() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, BF::ENABLE as u8]),
+        I2cTrans::read(DEV_ADDR, powered_up(0x1253)),
+    ];
+    let mut dev = Si4703::new_si4702(I2cMock::new(&transactions));
+    let mut delay = powerup_delay();
+    let result = dev.init(&mut delay, &Config::default());
+    assert!(matches!(
+        result,
+        Err(InitError::UnexpectedDevice(ChipInfo {
+            device: DeviceKind::Si4703,
+            ..
+        }))
+    ));
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn init_reports_failing_step() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, BF::ENABLE as u8]).with_error(ErrorKind::Other),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[DelayTrans::blocking_delay_ms(500)]);
+    let result = dev.init(&mut delay, &Config::default());
+    assert!(matches!(result, Err(InitError::Enable(Error::I2C(_)))));
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn init_rejects_invalid_config() {
+    let mut dev = new_si4703(&[]);
+    let mut delay = CheckedDelay::new(&[]);
+    let config = Config {
+        seek_snr_threshold: Snr::Enabled(8),
+        ..Config::default()
+    };
+    let result = dev.init(&mut delay, &config);
+    assert!(matches!(
+        result,
+        Err(InitError::ApplyConfig(Error::InvalidInputData))
+    ));
+    delay.done();
+    destroy(dev);
+}
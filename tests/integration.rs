@@ -1,11 +1,13 @@
-use embedded_hal_mock::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTrans};
 use si4703::{
-    Band, ChannelSpacing as Spacing, DeEmphasis, Gpio1Config, Gpio2Config, Gpio3Config, OutputMode,
-    Si4703, SoftmuteAttenuation, SoftmuteRate, StereoToMonoBlendLevel as Blend, Volume,
+    Band, ChannelSpacing as Spacing, DeEmphasis, Error, Frequency, Gpio1Config, Gpio2Config,
+    Gpio3Config, OutputMode, RdsBlockErrors, RdsMode, SeekFmImpulseThreshold as Cnt,
+    SeekSnrThreshold as Snr, Si4703, SoftmuteAttenuation, SoftmuteRate, Status,
+    StereoToMonoBlendLevel as Blend, Volume,
 };
 
 mod common;
-use self::common::{destroy, new_si4703, BitFlags as BF, DEV_ADDR};
+use self::common::{destroy, new_si4703, BitFlags as BF, Register, DEV_ADDR};
 
 #[test]
 fn can_create_and_destroy_si4702() {
@@ -13,6 +15,23 @@ fn can_create_and_destroy_si4702() {
     destroy(dev);
 }
 
+#[test]
+fn can_create_and_destroy_si4700() {
+    let dev = Si4703::new_si4700(I2cMock::new(&[]));
+    destroy(dev);
+}
+
+#[test]
+fn can_enable_rds_on_si4701() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0, (BF::RDS >> 8) as u8, 0]),
+    ];
+    let mut dev = Si4703::new_si4701(I2cMock::new(&transactions));
+    dev.enable_rds(RdsMode::Standard).unwrap();
+    destroy(dev);
+}
+
 write_test!(can_enable_osc, BF::XOSCEN, 16, 6, enable_oscillator);
 write_test!(can_enable_ahiz, BF::AHIZEN, 16, 6, enable_audio_high_z);
 write_test!(can_disable_ahiz, 0, 16, 6, disable_audio_high_z);
@@ -142,6 +161,24 @@ set_vol_test!(set_vol_m2, 14, 0, Dbfsm2);
 set_vol_test!(set_vol_0, 15, 0, Dbfs0);
 write_test!(can_set_mute_vol, 0, 16, 4, set_volume, Volume::Mute);
 
+#[test]
+fn cannot_set_extended_volume_on_si4700() {
+    let mut dev = Si4703::new_si4700(I2cMock::new(&[]));
+    assert_error!(dev.set_volume(Volume::Dbfsm30), Error::InvalidInputData);
+    destroy(dev);
+}
+
+#[test]
+fn can_set_volume_on_si4700() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0, 0, 0, 0, 1, 0, 0]),
+    ];
+    let mut dev = Si4703::new_si4700(I2cMock::new(&transactions));
+    dev.set_volume(Volume::Dbfsm28).unwrap();
+    destroy(dev);
+}
+
 write_test!(band_87_5_108, 0, 16, 4, set_band, Band::Mhz875_108);
 write_test!(band_76_108, 1 << 6, 16, 4, set_band, Band::Mhz76_108);
 write_test!(band_76_90, 2 << 6, 16, 4, set_band, Band::Mhz76_90);
@@ -150,6 +187,21 @@ write_test!(spc_200, 0, 16, 4, set_channel_spacing, Spacing::Khz200);
 write_test!(spc_100, 1 << 4, 16, 4, set_channel_spacing, Spacing::Khz100);
 write_test!(spc_50, 2 << 4, 16, 4, set_channel_spacing, Spacing::Khz50);
 
+#[test]
+fn configure_seek_keeps_volume_band_and_spacing() {
+    let mut data = [0; 32];
+    data[22] = 0x12;
+    data[23] = 0xDF;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, data.to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0, 0, 0, 0xAB, 0xDF, 0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.configure_seek(0xAB, Snr::default(), Cnt::default())
+        .unwrap();
+    destroy(dev);
+}
+
 write_test!(gpio1_hi, 0, 16, 3, set_gpio1, Gpio1Config::HighImpedance);
 write_test!(gpio1_high, 3, 16, 3, set_gpio1, Gpio1Config::High);
 write_test!(gpio1_low, 2, 16, 3, set_gpio1, Gpio1Config::Low);
@@ -169,16 +221,50 @@ write_test!(gpio3_low, 2 << 4, 16, 3, set_gpio3, Gpio3Config::Low);
 write_test!(dis_stci, 0, 16, 3, disable_stc_interrupts);
 write_test!(en_stci, BF::STCIEN, 16, 3, enable_stc_interrupts);
 
+#[test]
+fn registers_are_only_read_once() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(BF::DMUTE >> 8) as u8, BF::DMUTE as u8]),
+        I2cTrans::write(DEV_ADDR, vec![0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0, 0, 0, 0, 5, 0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.unmute().unwrap();
+    dev.mute().unwrap();
+    dev.set_volume(Volume::Dbfsm20).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_resync() {
+    let mut data = [0; 32];
+    data[16] = (BF::DMUTE >> 8) as u8;
+    data[17] = BF::DMUTE as u8;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::read(DEV_ADDR, data.to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.resync().unwrap();
+    dev.resync().unwrap();
+    dev.mute().unwrap();
+    destroy(dev);
+}
+
 macro_rules! get_channel_test {
     ($name:ident, $sysconfig2:expr, $readchan:expr, $value:expr) => {
         #[test]
         fn $name() {
             let mut data = [0; 32];
-            data[2] = ($readchan >> 8) as u8;
-            data[3] = $readchan as u8;
             data[11 * 2] = ($sysconfig2 >> 8) as u8;
             data[11 * 2 + 1] = $sysconfig2 as u8;
-            let transactions = [I2cTrans::read(DEV_ADDR, data.to_vec())];
+            let status = vec![0, 0, ($readchan >> 8) as u8, $readchan as u8];
+            let transactions = [
+                I2cTrans::read(DEV_ADDR, data.to_vec()),
+                I2cTrans::read(DEV_ADDR, status),
+            ];
             let mut dev = new_si4703(&transactions);
             let channel = dev.channel().unwrap();
             assert!(($value - 0.2) < channel);
@@ -195,8 +281,49 @@ get_channel_test!(get_channel_0_1_sp, 1 << 4, 100_u16, 87.5 + 100.0 * 0.1);
 get_channel_test!(get_channel_0_05_sp, 2 << 4, 100_u16, 87.5 + 100.0 * 0.05);
 get_channel_test!(get_chan_comb, 1 << 6 | 2 << 4, 100_u16, 76.0 + 100.0 * 0.05);
 
+macro_rules! get_channel_khz_test {
+    ($name:ident, $sysconfig2:expr, $readchan:expr, $khz:expr) => {
+        #[test]
+        fn $name() {
+            let mut data = [0; 32];
+            data[11 * 2] = ($sysconfig2 >> 8) as u8;
+            data[11 * 2 + 1] = $sysconfig2 as u8;
+            let status = vec![0, 0, ($readchan >> 8) as u8, $readchan as u8];
+            let transactions = [
+                I2cTrans::read(DEV_ADDR, data.to_vec()),
+                I2cTrans::read(DEV_ADDR, status),
+            ];
+            let mut dev = new_si4703(&transactions);
+            assert_eq!(Frequency::from_khz($khz), dev.channel_khz().unwrap());
+            destroy(dev);
+        }
+    };
+}
+
+get_channel_khz_test!(get_channel_khz_87_base, 0, 0, 87_500);
+get_channel_khz_test!(get_channel_khz_0_2_sp, 0, 54_u16, 98_300);
+get_channel_khz_test!(get_channel_khz_0_05_sp, 2 << 4, 11_u16, 88_050);
+get_channel_khz_test!(get_channel_khz_76_base, 1 << 6 | 1 << 4, 320_u16, 108_000);
+
+#[test]
+fn can_display_frequency() {
+    assert_eq!("98.30 MHz", Frequency::from_khz(98_300).to_string());
+    assert_eq!("88.05 MHz", Frequency::from_khz(88_050).to_string());
+    assert_eq!("107.90 MHz", Frequency::from_khz(107_900).to_string());
+}
+
 read_test!(out_mode_mono, 0, 2, OutputMode::Mono, output_mode);
 read_test!(out_mode_stereo, BF::ST, 2, OutputMode::Stereo, output_mode);
+read_test!(rssi_min, BF::ST, 2, 0, rssi);
+read_test!(rssi_max, BF::STC | 0xFF, 2, 0xFF, rssi);
+read_test!(rssi_value, 0x2A, 2, 0x2A, rssi);
+read_test!(
+    out_mode_and_rssi,
+    BF::ST | 0x2A,
+    2,
+    (OutputMode::Stereo, 0x2A),
+    output_mode_and_rssi
+);
 
 #[test]
 fn can_read_device_id() {
@@ -224,3 +351,221 @@ fn can_read_chip_id() {
     assert_eq!(firmware, 37);
     destroy(dev);
 }
+
+macro_rules! status_test {
+    ($name:ident, $statusrssi:expr, $readchan:expr, $expected:expr) => {
+        #[test]
+        fn $name() {
+            let status = vec![
+                ($statusrssi >> 8) as u8,
+                $statusrssi as u8,
+                ($readchan >> 8) as u8,
+                $readchan as u8,
+            ];
+            let transactions = [I2cTrans::read(DEV_ADDR, status)];
+            let mut dev = new_si4703(&transactions);
+            assert_eq!($expected, dev.status().unwrap());
+            destroy(dev);
+        }
+    };
+}
+
+status_test!(status_empty, 0_u16, 0_u16, Status::default());
+status_test!(
+    status_flags,
+    BF::RDSR | BF::STC | BF::SF_BL | BF::AFCRL | BF::RDSS | BF::ST,
+    0_u16,
+    Status {
+        rds_ready: true,
+        seek_tune_complete: true,
+        seek_failed_or_band_limit: true,
+        afc_rail: true,
+        rds_synchronized: true,
+        stereo: true,
+        ..Status::default()
+    }
+);
+status_test!(
+    status_rssi_and_channel,
+    0x2A_u16,
+    BF::BLERB1 | 0x3FF,
+    Status {
+        rssi: 0x2A,
+        channel: 0x3FF,
+        block_b_errors: RdsBlockErrors::ThreeToFive,
+        ..Status::default()
+    }
+);
+status_test!(
+    status_block_errors,
+    BF::BLERA0,
+    BF::BLERB0 | BF::BLERC1 | BF::BLERC0 | BF::BLERD1,
+    Status {
+        block_a_errors: RdsBlockErrors::OneOrTwo,
+        block_b_errors: RdsBlockErrors::OneOrTwo,
+        block_c_errors: RdsBlockErrors::TooMany,
+        block_d_errors: RdsBlockErrors::ThreeToFive,
+        ..Status::default()
+    }
+);
+
+const PCFG: usize = Register::POWERCFG;
+const SC1: usize = Register::SYSCONFIG1;
+const SC2: usize = Register::SYSCONFIG2;
+const SC3: usize = Register::SYSCONFIG3;
+const TEST1: usize = Register::TEST1;
+
+get_test!(osc_disabled, TEST1, 0, false, is_oscillator_enabled);
+get_test!(osc_enabled, TEST1, BF::XOSCEN, true, is_oscillator_enabled);
+get_test!(ahiz_disabled, TEST1, 0, false, is_audio_high_z_enabled);
+get_test!(
+    ahiz_enabled,
+    TEST1,
+    BF::AHIZEN,
+    true,
+    is_audio_high_z_enabled
+);
+get_test!(is_not_enabled, PCFG, 0, false, is_enabled);
+get_test!(is_enabled, PCFG, BF::ENABLE, true, is_enabled);
+get_test!(
+    is_disabled,
+    PCFG,
+    BF::ENABLE | BF::DISABLE,
+    false,
+    is_enabled
+);
+get_test!(is_muted, PCFG, 0, true, is_muted);
+get_test!(is_unmuted, PCFG, BF::DMUTE, false, is_muted);
+get_test!(softmute_disabled, PCFG, BF::DSMUTE, None, softmute);
+get_test!(
+    softmute_default,
+    PCFG,
+    0,
+    Some((SoftmuteRate::Fastest, SoftmuteAttenuation::Db16)),
+    softmute
+);
+get_test!(
+    cfg_out_stereo,
+    PCFG,
+    0,
+    OutputMode::Stereo,
+    configured_output_mode
+);
+get_test!(
+    cfg_out_mono,
+    PCFG,
+    BF::MONO,
+    OutputMode::Mono,
+    configured_output_mode
+);
+get_test!(de_75, SC1, 0, DeEmphasis::Us75, deemphasis);
+get_test!(de_50, SC1, BF::DE, DeEmphasis::Us50, deemphasis);
+get_test!(get_volume_mute, SC2, 0, Volume::Mute, volume);
+get_test!(get_volume_m28, SC2, 1, Volume::Dbfsm28, volume);
+get_test!(get_volume_0, SC2, 15, Volume::Dbfs0, volume);
+get_test!(get_band_87, SC2, 0, Band::Mhz875_108, band);
+get_test!(get_band_76_108, SC2, 1 << 6, Band::Mhz76_108, band);
+get_test!(get_band_76_90, SC2, 2 << 6, Band::Mhz76_90, band);
+get_test!(get_spacing_200, SC2, 0, Spacing::Khz200, channel_spacing);
+get_test!(
+    get_spacing_100,
+    SC2,
+    1 << 4,
+    Spacing::Khz100,
+    channel_spacing
+);
+get_test!(get_spacing_50, SC2, 2 << 4, Spacing::Khz50, channel_spacing);
+get_test!(stci_disabled, SC1, 0, false, is_stc_interrupts_enabled);
+get_test!(
+    stci_enabled,
+    SC1,
+    BF::STCIEN,
+    true,
+    is_stc_interrupts_enabled
+);
+get_test!(get_gpio1_hi_z, SC1, 0, Gpio1Config::HighImpedance, gpio1);
+get_test!(get_gpio1_low, SC1, 2, Gpio1Config::Low, gpio1);
+get_test!(get_gpio1_high, SC1, 3, Gpio1Config::High, gpio1);
+get_test!(get_gpio2_hi_z, SC1, 0, Gpio2Config::HighImpedance, gpio2);
+get_test!(
+    get_gpio2_int,
+    SC1,
+    1 << 2,
+    Gpio2Config::StcRdsInterrupt,
+    gpio2
+);
+get_test!(get_gpio2_low, SC1, 2 << 2, Gpio2Config::Low, gpio2);
+get_test!(get_gpio2_high, SC1, 3 << 2, Gpio2Config::High, gpio2);
+get_test!(get_gpio3_hi_z, SC1, 0, Gpio3Config::HighImpedance, gpio3);
+get_test!(
+    get_gpio3_st,
+    SC1,
+    1 << 4,
+    Gpio3Config::MonoStereoIndicator,
+    gpio3
+);
+get_test!(get_gpio3_low, SC1, 2 << 4, Gpio3Config::Low, gpio3);
+get_test!(get_gpio3_high, SC1, 3 << 4, Gpio3Config::High, gpio3);
+get_test!(agc_enabled, SC1, 0, true, is_auto_gain_control_enabled);
+get_test!(
+    agc_disabled,
+    SC1,
+    BF::AGCD,
+    false,
+    is_auto_gain_control_enabled
+);
+get_test!(get_blend_31, SC1, 0, Blend::Dbuv31_49, blend_level);
+get_test!(get_blend_37, SC1, 1 << 6, Blend::Dbuv37_55, blend_level);
+get_test!(get_blend_19, SC1, 2 << 6, Blend::Dbuv19_37, blend_level);
+get_test!(get_blend_25, SC1, 3 << 6, Blend::Dbuv25_43, blend_level);
+get_test!(
+    get_seek_config_rssi,
+    SC2,
+    0xAB00_u16,
+    (0xAB, Snr::Disabled, Cnt::Disabled),
+    seek_config
+);
+get_test!(
+    get_seek_config_th,
+    SC3,
+    7 << 4 | 15,
+    (0, Snr::Enabled(7), Cnt::Enabled(15)),
+    seek_config
+);
+
+#[test]
+fn get_volume_extended() {
+    let mut data = [0; 32];
+    data[11 * 2 + 1] = 1;
+    data[12 * 2] = (BF::VOLEXT >> 8) as u8;
+    let transactions = [I2cTrans::read(DEV_ADDR, data.to_vec())];
+    let mut dev = new_si4703(&transactions);
+    assert_eq!(Volume::Dbfsm58, dev.volume().unwrap());
+    destroy(dev);
+}
+
+#[test]
+fn get_softmute_configured() {
+    let mut data = [0; 32];
+    data[12 * 2] = 3 << 6 | 2 << 4;
+    let transactions = [I2cTrans::read(DEV_ADDR, data.to_vec())];
+    let mut dev = new_si4703(&transactions);
+    assert_eq!(
+        Some((SoftmuteRate::Slowest, SoftmuteAttenuation::Db12)),
+        dev.softmute().unwrap()
+    );
+    destroy(dev);
+}
+
+#[test]
+fn getters_use_cached_registers() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0, 0, 0, 0, 5, 0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.set_volume(Volume::Dbfsm20).unwrap();
+    assert_eq!(Volume::Dbfsm20, dev.volume().unwrap());
+    assert!(dev.is_muted().unwrap());
+    destroy(dev);
+}
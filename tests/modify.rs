@@ -0,0 +1,111 @@
+use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+use si4703::{
+    Error, Gpio1Config, OutputMode, RdsMode, SeekFmImpulseThreshold as Cnt,
+    SeekSnrThreshold as Snr, Si4703, Volume,
+};
+
+mod common;
+use self::common::{destroy, new_si4703, BitFlags as BF, DEV_ADDR};
+
+#[test]
+fn writes_only_powercfg() {
+    let powercfg = BF::DMUTE | BF::MONO;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(powercfg >> 8) as u8, powercfg as u8]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.modify(|m| {
+        m.unmute();
+        m.set_output_mode(OutputMode::Mono);
+    })
+    .unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn writes_up_to_last_changed_register() {
+    let powercfg = BF::DMUTE | BF::MONO;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![(powercfg >> 8) as u8, powercfg as u8, 0, 0, 0, 0, 0, 5],
+        ),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.modify(|m| {
+        m.set_volume(Volume::Dbfsm20)
+            .unmute()
+            .set_output_mode(OutputMode::Mono);
+    })
+    .unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn writes_until_test1() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, 0, 0, 0, 3, 0, 0, 0, 0, (BF::XOSCEN >> 8) as u8, 0],
+        ),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.modify(|m| {
+        m.set_gpio1(Gpio1Config::High).enable_oscillator();
+    })
+    .unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn nothing_written_if_unchanged() {
+    let transactions = [I2cTrans::read(DEV_ADDR, [0; 32].to_vec())];
+    let mut dev = new_si4703(&transactions);
+    dev.modify(|m| {
+        m.mute().set_volume(Volume::Mute);
+    })
+    .unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_modify_rds() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, 0, 0, ((BF::RDS | BF::RDSIEN) >> 8) as u8, 0],
+        ),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.modify(|m| {
+        m.enable_rds(RdsMode::Standard).enable_rds_interrupts();
+    })
+    .unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn invalid_input_writes_nothing() {
+    let transactions = [I2cTrans::read(DEV_ADDR, [0; 32].to_vec())];
+    let mut dev = new_si4703(&transactions);
+    let result = dev.modify(|m| {
+        m.unmute().configure_seek(0, Snr::Enabled(8), Cnt::Disabled);
+    });
+    assert_error!(result, Error::InvalidInputData);
+    destroy(dev);
+}
+
+#[test]
+fn extended_volume_on_si4700_writes_nothing() {
+    let transactions = [I2cTrans::read(DEV_ADDR, [0; 32].to_vec())];
+    let mut dev = Si4703::new_si4700(I2cMock::new(&transactions));
+    let result = dev.modify(|m| {
+        m.unmute().set_volume(Volume::Dbfsm58);
+    });
+    assert_error!(result, Error::InvalidInputData);
+    destroy(dev);
+}
@@ -0,0 +1,135 @@
+use embedded_hal_mock::eh1::{
+    delay::{CheckedDelay, Transaction as DelayTrans},
+    i2c::Transaction as I2cTrans,
+};
+
+mod common;
+use self::common::{destroy, new_si4703, BitFlags as BF, DEV_ADDR};
+
+const POWERCFG: u16 = BF::DMUTE | BF::ENABLE;
+const SYSCONFIG1: u16 = BF::RDS | 1 << 2 | 3;
+
+fn running() -> Vec<u8> {
+    let mut data = [0; 32];
+    data[16] = (POWERCFG >> 8) as u8;
+    data[17] = POWERCFG as u8;
+    data[20] = (SYSCONFIG1 >> 8) as u8;
+    data[21] = SYSCONFIG1 as u8;
+    data.to_vec()
+}
+
+fn powerup_delay() -> CheckedDelay {
+    CheckedDelay::new(&[DelayTrans::blocking_delay_ms(110)])
+}
+
+#[test]
+fn can_power_down() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, running()),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 0, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 0x41]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.power_down().unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_power_down_with_audio_high_z() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, running()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0x40, 1, 0, 0, 0, 0, 0, 0, 0, 0, (BF::AHIZEN >> 8) as u8, 0],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 0x41]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.power_down_with_audio_high_z().unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn power_up_restores_previous_settings() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, running()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0x40, 1, 0, 0, 0, 0, 0, 0, 0, 0, (BF::AHIZEN >> 8) as u8, 0],
+        ),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 0x41]),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 0, 0x10, 0x07, 0, 0, 0, 0, 0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.power_down_with_audio_high_z().unwrap();
+    let mut delay = powerup_delay();
+    dev.power_up(&mut delay).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn power_up_restores_audio_high_z_when_enabling() {
+    let mut data = running();
+    data[26] = (BF::AHIZEN >> 8) as u8;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, data),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 0, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 0x41]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                0x40,
+                1,
+                0,
+                0,
+                0x10,
+                0x07,
+                0,
+                0,
+                0,
+                0,
+                (BF::AHIZEN >> 8) as u8,
+                0,
+            ],
+        ),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.power_down().unwrap();
+    let mut delay = powerup_delay();
+    dev.power_up(&mut delay).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn repeated_power_down_keeps_original_settings() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, running()),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 0, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 0x41]),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 0x41, 0, 0, 0, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 0x41]),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 0, 0x10, 0x07, 0, 0, 0, 0, 0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    dev.power_down().unwrap();
+    dev.power_down().unwrap();
+    let mut delay = powerup_delay();
+    dev.power_up(&mut delay).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn power_up_without_power_down_only_enables() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 1]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = powerup_delay();
+    dev.power_up(&mut delay).unwrap();
+    delay.done();
+    destroy(dev);
+}
@@ -1,11 +1,11 @@
-use embedded_hal_mock::i2c::Transaction as I2cTrans;
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
 use si4703::{
     fill_with_rds_radio_text, get_rds_radio_text, RdsBlockData, RdsBlockErrors, RdsData, RdsMode,
     RdsRadioText, RdsRadioTextData,
 };
 
 mod common;
-use self::common::{destroy, new_si4703, BitFlags as BF, DEV_ADDR};
+use self::common::{destroy, new_si4703, BitFlags as BF, Register, DEV_ADDR};
 
 write_test!(en_rds_std, BF::RDS, 16, 3, enable_rds, RdsMode::Standard);
 
@@ -33,6 +33,43 @@ fn can_enable_rds_verbose() {
 write_test!(en_rds_int, BF::RDSIEN, 16, 3, enable_rds_interrupts);
 write_test!(dis_rds_int, 0, 16, 3, disable_rds_interrupts);
 
+get_test!(rds_disabled, Register::SYSCONFIG1, 0, false, is_rds_enabled);
+get_test!(
+    rds_enabled,
+    Register::SYSCONFIG1,
+    BF::RDS,
+    true,
+    is_rds_enabled
+);
+get_test!(
+    rds_mode_std,
+    Register::POWERCFG,
+    0,
+    RdsMode::Standard,
+    rds_mode
+);
+get_test!(
+    rds_mode_verbose,
+    Register::POWERCFG,
+    BF::RDSM,
+    RdsMode::Verbose,
+    rds_mode
+);
+get_test!(
+    rds_int_disabled,
+    Register::SYSCONFIG1,
+    0,
+    false,
+    is_rds_interrupts_enabled
+);
+get_test!(
+    rds_int_enabled,
+    Register::SYSCONFIG1,
+    BF::RDSIEN,
+    true,
+    is_rds_interrupts_enabled
+);
+
 read_test!(rds_not_sync, 0, 2, false, rds_synchronized);
 read_test!(rds_sync, BF::RDSS, 2, true, rds_synchronized);
 
@@ -285,7 +322,7 @@ mod fill_radio_text {
     fn empty_data() {
         let data = RdsData::default();
         let mut text = EMPTY;
-        assert_eq!(false, fill_with_rds_radio_text(&mut text, &data));
+        assert!(!fill_with_rds_radio_text(&mut text, &data));
         array_eq!(EMPTY, text);
     }
 
@@ -303,7 +340,7 @@ mod fill_radio_text {
             ..Default::default()
         };
         let mut text = EMPTY;
-        assert_eq!(false, fill_with_rds_radio_text(&mut text, &data));
+        assert!(!fill_with_rds_radio_text(&mut text, &data));
         array_eq!(EMPTY, text);
     }
 
@@ -328,7 +365,7 @@ mod fill_radio_text {
     #[test]
     fn can_read_two() {
         let mut text = EMPTY;
-        assert_eq!(false, fill_with_rds_radio_text(&mut text, &DATA_AB));
+        assert!(!fill_with_rds_radio_text(&mut text, &DATA_AB));
         let mut expected = EMPTY;
         expected[0..2].copy_from_slice(&['A', 'B']);
         array_eq!(expected, text);
@@ -337,7 +374,7 @@ mod fill_radio_text {
     #[test]
     fn can_read_four() {
         let mut text = EMPTY;
-        assert_eq!(false, fill_with_rds_radio_text(&mut text, &DATA_ABCD));
+        assert!(!fill_with_rds_radio_text(&mut text, &DATA_ABCD));
         let mut expected = EMPTY;
         expected[0..4].copy_from_slice(&['A', 'B', 'C', 'D']);
         array_eq!(expected, text);
@@ -0,0 +1,105 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use si4703::{Band, ChannelSpacing, DeEmphasis, Region};
+
+mod common;
+use self::common::{destroy, new_si4703, BitFlags as BF, DEV_ADDR};
+
+write_test!(set_region_usa, 0, 16, 4, set_region, Region::Usa);
+write_test!(
+    set_region_europe,
+    16,
+    2,
+    BF::DE,
+    3,
+    1 << 4,
+    set_region,
+    Region::Europe
+);
+write_test!(
+    set_region_japan,
+    16,
+    2,
+    BF::DE,
+    3,
+    2 << 6 | 1 << 4,
+    set_region,
+    Region::Japan
+);
+write_test!(
+    set_region_japan_wide,
+    16,
+    2,
+    BF::DE,
+    3,
+    1 << 6 | 1 << 4,
+    set_region,
+    Region::JapanWide
+);
+write_test!(
+    set_region_australia,
+    16,
+    2,
+    BF::DE,
+    3,
+    0,
+    set_region,
+    Region::Australia
+);
+write_test!(
+    set_region_italy,
+    16,
+    2,
+    BF::DE,
+    3,
+    2 << 4,
+    set_region,
+    Region::Italy
+);
+
+macro_rules! get_region_test {
+    ($name:ident, $sysconfig1:expr, $sysconfig2:expr, $expected:expr) => {
+        #[test]
+        fn $name() {
+            let mut data = [0; 32];
+            data[20] = ($sysconfig1 >> 8) as u8;
+            data[21] = $sysconfig1 as u8;
+            data[22] = ($sysconfig2 >> 8) as u8;
+            data[23] = $sysconfig2 as u8;
+            let transactions = [I2cTrans::read(DEV_ADDR, data.to_vec())];
+            let mut dev = new_si4703(&transactions);
+            assert_eq!($expected, dev.region().unwrap());
+            destroy(dev);
+        }
+    };
+}
+
+get_region_test!(get_region_usa, 0, 0, Some(Region::Usa));
+get_region_test!(get_region_europe, BF::DE, 1 << 4, Some(Region::Europe));
+get_region_test!(
+    get_region_japan,
+    BF::DE,
+    2 << 6 | 1 << 4,
+    Some(Region::Japan)
+);
+get_region_test!(get_region_italy, BF::DE, 2 << 4, Some(Region::Italy));
+get_region_test!(get_region_none, 0, 2 << 6 | 2 << 4, None);
+
+#[test]
+fn regions_are_distinct() {
+    for (i, a) in Region::ALL.iter().enumerate() {
+        for b in &Region::ALL[i + 1..] {
+            assert_ne!(
+                (a.band(), a.channel_spacing(), a.deemphasis()),
+                (b.band(), b.channel_spacing(), b.deemphasis())
+            );
+        }
+    }
+}
+
+#[test]
+fn can_get_region_settings() {
+    let region = Region::Europe;
+    assert_eq!(Band::Mhz875_108, region.band());
+    assert_eq!(ChannelSpacing::Khz100, region.channel_spacing());
+    assert_eq!(DeEmphasis::Us50, region.deemphasis());
+}
@@ -0,0 +1,158 @@
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+use si4703::registers::{
+    ChipId, PowerCfg, ReadChan, RegisterDump, StatusRssi, SysConfig1, SysConfig2,
+};
+
+mod common;
+use self::common::{destroy, new_si4703, BitFlags as BF, DEV_ADDR};
+
+#[test]
+fn can_read_status_register() {
+    let transactions = [I2cTrans::read(
+        DEV_ADDR,
+        vec![((BF::STC | BF::ST) >> 8) as u8, 0x2A],
+    )];
+    let mut dev = new_si4703(&transactions);
+    let status: StatusRssi = dev.read_register().unwrap();
+    assert!(status.stc());
+    assert!(status.st());
+    assert!(!status.sf_bl());
+    assert_eq!(0x2A, status.rssi());
+    destroy(dev);
+}
+
+#[test]
+fn can_read_readchan_register() {
+    let transactions = [I2cTrans::read(DEV_ADDR, vec![0, 0, 0xC1, 0x23])];
+    let mut dev = new_si4703(&transactions);
+    let readchan: ReadChan = dev.read_register().unwrap();
+    assert_eq!(3, readchan.blerb());
+    assert_eq!(0x123, readchan.readchan());
+    destroy(dev);
+}
+
+#[test]
+fn can_read_chip_id_register() {
+    let mut data = [0; 32];
+    data[14] = 0x12;
+    data[15] = 0x53;
+    let transactions = [I2cTrans::read(DEV_ADDR, data.to_vec())];
+    let mut dev = new_si4703(&transactions);
+    let chip_id: ChipId = dev.read_register().unwrap();
+    assert_eq!(4, chip_id.rev());
+    assert_eq!(9, chip_id.dev());
+    assert_eq!(0x13, chip_id.firmware());
+    destroy(dev);
+}
+
+#[test]
+fn can_write_powercfg() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(BF::DMUTE >> 8) as u8, BF::ENABLE as u8]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut powercfg = PowerCfg::default();
+    powercfg.set_dmute(true).set_enable(true);
+    dev.write_register(powercfg).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn write_keeps_cached_previous_registers() {
+    let mut data = [0; 32];
+    data[16] = (BF::DMUTE >> 8) as u8;
+    data[20] = (BF::RDS >> 8) as u8;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, data.to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![
+                (BF::DMUTE >> 8) as u8,
+                0,
+                0,
+                0,
+                (BF::RDS >> 8) as u8,
+                0,
+                0,
+                0x1F,
+            ],
+        ),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut sysconfig2 = SysConfig2::default();
+    sysconfig2.set_space(1).set_volume(0xF);
+    dev.write_register(sysconfig2).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn read_then_write_updates_single_field() {
+    let mut data = [0; 32];
+    data[20] = 0x10;
+    data[21] = 0x05;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, data.to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0, 0x10, 0x09]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut sysconfig1: SysConfig1 = dev.read_register().unwrap();
+    sysconfig1.set_gpio2(2);
+    dev.write_register(sysconfig1).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_read_register_dump() {
+    let mut data = [0; 32];
+    data[0] = ((BF::STC | BF::ST) >> 8) as u8;
+    data[1] = 0x2A;
+    data[16] = (BF::DMUTE >> 8) as u8;
+    data[17] = BF::ENABLE as u8;
+    let transactions = [I2cTrans::read(DEV_ADDR, data.to_vec())];
+    let mut dev = new_si4703(&transactions);
+    let dump = dev.read_registers().unwrap();
+    assert_eq!(0x4001, u16::from(dump.get::<PowerCfg>()));
+    assert_eq!(0x412A, dump.raw()[0x0A]);
+    destroy(dev);
+}
+
+#[test]
+fn can_display_register_dump() {
+    let mut registers = [0; 16];
+    registers[0x00] = 0x1242;
+    registers[0x02] = 0x4001;
+    registers[0x05] = 0x1F1F;
+    registers[0x0A] = 0x412A;
+    registers[0x0B] = 0xC123;
+    let text = format!("{}", RegisterDump::from(registers));
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(16, lines.len());
+    assert_eq!("0x00 DEVICEID   0x1242 PN=1 MFGID=578", lines[0]);
+    assert_eq!(
+        "0x02 POWERCFG   0x4001 DSMUTE=0 DMUTE=1 MONO=0 RDSM=0 SKMODE=0 SEEKUP=0 SEEK=0 DISABLE=0 ENABLE=1",
+        lines[2]
+    );
+    assert_eq!(
+        "0x05 SYSCONFIG2 0x1F1F SEEKTH=31 BAND=0 SPACE=1 VOLUME=15",
+        lines[5]
+    );
+    assert_eq!(
+        "0x0A STATUSRSSI 0x412A RDSR=0 STC=1 SF_BL=0 AFCRL=0 RDSS=0 BLERA=0 ST=1 RSSI=42",
+        lines[10]
+    );
+    assert_eq!(
+        "0x0B READCHAN   0xC123 BLERB=3 BLERC=0 BLERD=0 READCHAN=291",
+        lines[11]
+    );
+    assert_eq!("0x0F RDSD       0x0000 RDSD=0", lines[15]);
+}
+
+#[test]
+fn can_debug_print_register_dump() {
+    let mut registers = [0; 16];
+    registers[0x03] = 0x8005;
+    let text = format!("{:?}", RegisterDump::from(registers));
+    assert!(text.starts_with("RegisterDump { DEVICEID: DeviceId { PN: 0, MFGID: 0 }"));
+    assert!(text.contains("CHANNEL: Channel { TUNE: true, CHAN: 5 }"));
+}
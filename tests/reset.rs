@@ -1,6 +1,6 @@
-use embedded_hal_mock::{
-    delay::MockNoop as NoopDelay,
-    pin::{Mock as PinMock, State as PinState, Transaction as PinTrans},
+use embedded_hal_mock::eh1::{
+    delay::NoopDelay,
+    digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
 };
 use si4703::{
     reset_and_select_i2c_method1, reset_and_select_i2c_method1_with_gpio3,
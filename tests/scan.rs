@@ -0,0 +1,169 @@
+use embedded_hal_mock::eh1::{
+    delay::{CheckedDelay, Transaction as DelayTrans},
+    i2c::Transaction as I2cTrans,
+};
+use nb::block;
+use si4703::{Error, Frequency, SeekDirection, SeekMode, Station, TuneChannel};
+
+mod common;
+use self::common::{destroy, new_si4703, BitFlags as BF, DEV_ADDR};
+
+const SEEK_UP: u16 = BF::SEEK | BF::SEEKUP;
+
+fn status(statusrssi: u16, readchan: u16) -> Vec<u8> {
+    vec![
+        (statusrssi >> 8) as u8,
+        statusrssi as u8,
+        (readchan >> 8) as u8,
+        readchan as u8,
+    ]
+}
+
+fn tune_to_bottom() -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, (BF::TUNE >> 8) as u8, 0]),
+        I2cTrans::read(DEV_ADDR, status(BF::STC, 0)),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0]),
+        I2cTrans::read(DEV_ADDR, status(0, 0)),
+    ]
+}
+
+fn seek_up(statusrssi: u16, readchan: u16) -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::read(DEV_ADDR, status(0, 0)),
+        I2cTrans::write(DEV_ADDR, vec![(SEEK_UP >> 8) as u8, 0]),
+        I2cTrans::read(DEV_ADDR, status(BF::STC | statusrssi, readchan)),
+        I2cTrans::write(DEV_ADDR, vec![(BF::SEEKUP >> 8) as u8, 0]),
+        I2cTrans::read(DEV_ADDR, status(0, readchan)),
+    ]
+}
+
+fn scan_transactions() -> Vec<I2cTrans> {
+    let mut transactions = tune_to_bottom();
+    transactions.extend(seek_up(BF::ST | 0x2A, 6));
+    transactions.extend(seek_up(BF::AFCRL | 0x10, 8));
+    transactions.extend(seek_up(BF::SF_BL | 0x05, 102));
+    transactions
+}
+
+fn expected_station() -> Station {
+    Station {
+        frequency: Frequency::from_khz(88_700),
+        rssi: 0x2A,
+        stereo: true,
+    }
+}
+
+#[test]
+fn can_scan_band() {
+    let mut dev = new_si4703(&scan_transactions());
+    let mut stations = [Station::default(); 4];
+    let mut reached = Vec::new();
+    let count = block!(dev.scan_band(&mut stations, |frequency| reached.push(frequency))).unwrap();
+    assert_eq!(1, count);
+    assert_eq!(expected_station(), stations[0]);
+    assert_eq!(
+        vec![
+            Frequency::from_khz(88_700),
+            Frequency::from_khz(89_100),
+            Frequency::from_khz(107_900)
+        ],
+        reached
+    );
+    assert!(!dev.is_busy());
+    destroy(dev);
+}
+
+#[test]
+fn can_scan_band_blocking() {
+    let mut dev = new_si4703(&scan_transactions());
+    let mut stations = [Station::default(); 4];
+    let mut delay = CheckedDelay::new(&vec![DelayTrans::blocking_delay_ms(10); 8]);
+    let count = dev
+        .scan_band_blocking(&mut stations, &mut delay, 1000, |_| ())
+        .unwrap();
+    assert_eq!(1, count);
+    assert_eq!(expected_station(), stations[0]);
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn scan_stops_when_buffer_is_full() {
+    let mut transactions = tune_to_bottom();
+    transactions.extend(seek_up(BF::ST | 0x2A, 6));
+    let mut dev = new_si4703(&transactions);
+    let mut stations = [Station::default(); 1];
+    let count = block!(dev.scan_band(&mut stations, |_| ())).unwrap();
+    assert_eq!(1, count);
+    assert_eq!(expected_station(), stations[0]);
+    destroy(dev);
+}
+
+#[test]
+fn cannot_scan_while_seeking() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(BF::SEEK >> 8) as u8, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let result = dev.seek(SeekMode::NoWrap, SeekDirection::Down);
+    assert!(matches!(result, Err(nb::Error::WouldBlock)));
+    let mut stations = [Station::default(); 1];
+    let result = dev.scan_band(&mut stations, |_| ());
+    assert!(matches!(result, Err(nb::Error::Other(Error::Busy))));
+    destroy(dev);
+}
+
+#[test]
+fn scan_with_empty_buffer_does_nothing() {
+    let mut dev = new_si4703(&[]);
+    let count = block!(dev.scan_band(&mut [], |_| ())).unwrap();
+    assert_eq!(0, count);
+    let mut delay = CheckedDelay::new(&[]);
+    let count = dev.scan_band_blocking(&mut [], &mut delay, 1000, |_| ());
+    assert_eq!(0, count.unwrap());
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn cannot_seek_or_tune_while_scanning() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, (BF::TUNE >> 8) as u8, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut stations = [Station::default(); 1];
+    let result = dev.scan_band(&mut stations, |_| ());
+    assert!(matches!(result, Err(nb::Error::WouldBlock)));
+    let result = dev.seek(SeekMode::NoWrap, SeekDirection::Up);
+    assert!(matches!(result, Err(nb::Error::Other(Error::Busy))));
+    let result = dev.tune(TuneChannel::Raw(0));
+    assert!(matches!(result, Err(nb::Error::Other(Error::Busy))));
+    destroy(dev);
+}
+
+#[test]
+fn can_seek_after_cancelling_scan() {
+    let mut tuning_data = [0; 32];
+    tuning_data[18] = (BF::TUNE >> 8) as u8;
+    let mut transactions = vec![
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, (BF::TUNE >> 8) as u8, 0]),
+        I2cTrans::read(DEV_ADDR, tuning_data[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 0]),
+        I2cTrans::read(DEV_ADDR, [0; 4].to_vec()),
+    ];
+    transactions.extend(seek_up(BF::ST | 0x2A, 6));
+    let mut dev = new_si4703(&transactions);
+    let mut stations = [Station::default(); 1];
+    let result = dev.scan_band(&mut stations, |_| ());
+    assert!(matches!(result, Err(nb::Error::WouldBlock)));
+    block!(dev.cancel_scan()).unwrap();
+    assert!(!dev.is_busy());
+    let outcome = block!(dev.seek(SeekMode::NoWrap, SeekDirection::Up)).unwrap();
+    assert_eq!(Frequency::from_khz(88_700), outcome.frequency);
+    destroy(dev);
+}
@@ -1,11 +1,12 @@
-use embedded_hal_mock::{
+use embedded_hal_mock::eh1::{
+    delay::{CheckedDelay, Transaction as DelayTrans},
+    digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
     i2c::Transaction as I2cTrans,
-    pin::{Mock as PinMock, State as PinState, Transaction as PinTrans},
 };
 use nb::block;
 use si4703::{
-    Error, ErrorWithPin, SeekDirection, SeekFmImpulseThreshold as Cnt, SeekMode,
-    SeekSnrThreshold as Snr,
+    Error, Frequency, Operation, SeekDirection, SeekFmImpulseThreshold as Cnt, SeekMode,
+    SeekOutcome, SeekSnrThreshold as Snr, TuneChannel,
 };
 
 mod common;
@@ -38,6 +39,7 @@ macro_rules! invalid_config_seek_test {
         fn $name() {
             let mut dev = new_si4703(&[]);
             assert_error!(dev.configure_seek(0, $snr, $cnt), Error::InvalidInputData);
+            destroy(dev);
         }
     };
 }
@@ -66,8 +68,8 @@ macro_rules! seek_test {
             let transactions = [
                 I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
                 I2cTrans::write(DEV_ADDR, vec![(powercfg >> 8) as u8, powercfg as u8]),
-                I2cTrans::read(DEV_ADDR, seeking_data.to_vec()),
-                I2cTrans::read(DEV_ADDR, seeking_found_data.to_vec()),
+                I2cTrans::read(DEV_ADDR, seeking_data[..4].to_vec()),
+                I2cTrans::read(DEV_ADDR, seeking_found_data[..4].to_vec()),
                 I2cTrans::write(
                     DEV_ADDR,
                     vec![
@@ -75,8 +77,8 @@ macro_rules! seek_test {
                         (powercfg & !BF::SEEK) as u8,
                     ],
                 ),
-                I2cTrans::read(DEV_ADDR, found_data.to_vec()),
-                I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+                I2cTrans::read(DEV_ADDR, found_data[..4].to_vec()),
+                I2cTrans::read(DEV_ADDR, [0; 32][..4].to_vec()),
             ];
             let mut dev = new_si4703(&transactions);
             block!(dev.seek(SeekMode::$mode, SeekDirection::$direction)).unwrap();
@@ -106,18 +108,219 @@ fn can_seek() {
     let transactions = [
         I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
         I2cTrans::write(DEV_ADDR, vec![(BF::SEEK >> 8) as u8, BF::SEEK as u8]),
-        I2cTrans::read(DEV_ADDR, seeking_data.to_vec()),
-        I2cTrans::read(DEV_ADDR, seeking_found_data.to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_found_data[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0]),
+        I2cTrans::read(DEV_ADDR, found_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, [0; 32][..4].to_vec()),
+    ];
+    let mut dev = new_si4703(&transactions);
+    block!(dev.seek(SeekMode::NoWrap, SeekDirection::Down)).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_seek_blocking() {
+    let mut found_data = [0; 32];
+    found_data[0] = (BF::STC >> 8) as u8;
+    found_data[1] = BF::STC as u8;
+    let mut seeking_data = [0; 32];
+    seeking_data[16] = (BF::SEEK >> 8) as u8;
+    seeking_data[17] = BF::SEEK as u8;
+    let mut seeking_found_data = seeking_data;
+    seeking_found_data[0] = ((BF::STC | BF::ST) >> 8) as u8;
+    seeking_found_data[1] = 0x2A;
+    seeking_found_data[3] = 3;
+    let mut done_data = [0; 32];
+    done_data[3] = 3;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(BF::SEEK >> 8) as u8, BF::SEEK as u8]),
+        I2cTrans::read(DEV_ADDR, seeking_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_found_data[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0]),
+        I2cTrans::read(DEV_ADDR, found_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, done_data[..4].to_vec()),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&vec![DelayTrans::blocking_delay_ms(10); 4]);
+    let outcome = dev
+        .seek_blocking(SeekMode::NoWrap, SeekDirection::Down, &mut delay, 100)
+        .unwrap();
+    assert_eq!(
+        SeekOutcome {
+            frequency: Frequency::from_khz(88_100),
+            rssi: 0x2A,
+            stereo: true,
+            afc_railed: false,
+            band_limit: false,
+        },
+        outcome
+    );
+    assert!(outcome.found());
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn seek_blocking_times_out() {
+    let mut seeking_data = [0; 32];
+    seeking_data[16] = (BF::SEEK >> 8) as u8;
+    seeking_data[17] = BF::SEEK as u8;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(BF::SEEK >> 8) as u8, BF::SEEK as u8]),
+        I2cTrans::read(DEV_ADDR, seeking_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_data[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0]),
+        I2cTrans::read(DEV_ADDR, [0; 4].to_vec()),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&vec![DelayTrans::blocking_delay_ms(10); 3]);
+    let result = dev.seek_blocking(SeekMode::NoWrap, SeekDirection::Down, &mut delay, 20);
+    assert_error!(result, Error::Timeout);
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_cancel_seek() {
+    let mut seeking_data = [0; 32];
+    seeking_data[16] = (BF::SEEK >> 8) as u8;
+    seeking_data[17] = BF::SEEK as u8;
+    let mut stopping_data = [0; 32];
+    stopping_data[0] = (BF::STC >> 8) as u8;
+    stopping_data[1] = BF::STC as u8;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(BF::SEEK >> 8) as u8, BF::SEEK as u8]),
+        I2cTrans::read(DEV_ADDR, seeking_data[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0]),
+        I2cTrans::read(DEV_ADDR, stopping_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, [0; 4].to_vec()),
+        I2cTrans::read(DEV_ADDR, [0; 4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(BF::SEEK >> 8) as u8, BF::SEEK as u8]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let result = dev.seek(SeekMode::NoWrap, SeekDirection::Down);
+    assert!(matches!(result, Err(nb::Error::WouldBlock)));
+    block!(dev.cancel_seek()).unwrap();
+    // A new seek can be started afterwards.
+    let result = dev.seek(SeekMode::NoWrap, SeekDirection::Down);
+    assert!(matches!(result, Err(nb::Error::WouldBlock)));
+    destroy(dev);
+}
+
+#[test]
+fn cannot_tune_while_seeking() {
+    let mut seeking_data = [0; 32];
+    seeking_data[16] = (BF::SEEK >> 8) as u8;
+    seeking_data[17] = BF::SEEK as u8;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(BF::SEEK >> 8) as u8, BF::SEEK as u8]),
+        I2cTrans::read(DEV_ADDR, seeking_data[..4].to_vec()),
         I2cTrans::write(DEV_ADDR, vec![0, 0]),
-        I2cTrans::read(DEV_ADDR, found_data.to_vec()),
+        I2cTrans::read(DEV_ADDR, [0; 4].to_vec()),
+    ];
+    let mut dev = new_si4703(&transactions);
+    assert!(!dev.is_busy());
+    assert_eq!(None, dev.current_operation());
+    let result = dev.seek(SeekMode::NoWrap, SeekDirection::Down);
+    assert!(matches!(result, Err(nb::Error::WouldBlock)));
+    assert!(dev.is_busy());
+    assert_eq!(Some(Operation::Seek), dev.current_operation());
+    let result = dev.tune(TuneChannel::Raw(2));
+    assert!(matches!(result, Err(nb::Error::Other(Error::Busy))));
+    let result = dev.cancel_tune();
+    assert!(matches!(result, Err(nb::Error::Other(Error::Busy))));
+    block!(dev.cancel_seek()).unwrap();
+    assert!(!dev.is_busy());
+    assert_eq!(None, dev.current_operation());
+    destroy(dev);
+}
+
+#[test]
+fn seek_lost_by_device_is_aborted() {
+    let transactions = [
         I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(BF::SEEK >> 8) as u8, BF::SEEK as u8]),
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::read(DEV_ADDR, [0; 4].to_vec()),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let result = dev.seek(SeekMode::NoWrap, SeekDirection::Down);
+    assert!(matches!(result, Err(nb::Error::WouldBlock)));
+    // the device was reset
+    dev.resync().unwrap();
+    let result = dev.seek(SeekMode::NoWrap, SeekDirection::Down);
+    assert!(matches!(result, Err(nb::Error::Other(Error::Aborted))));
+    destroy(dev);
+}
+
+#[test]
+fn seek_lost_by_device_without_resync_is_aborted() {
+    let mut seeking_data = [0; 20];
+    seeking_data[16] = (BF::SEEK >> 8) as u8;
+    let mut transactions = vec![
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![(BF::SEEK >> 8) as u8, 0]),
+    ];
+    transactions.extend(vec![I2cTrans::read(DEV_ADDR, vec![0; 4]); 9]);
+    transactions.push(I2cTrans::read(DEV_ADDR, seeking_data.to_vec()));
+    transactions.extend(vec![I2cTrans::read(DEV_ADDR, vec![0; 4]); 9]);
+    // the device was reset
+    transactions.push(I2cTrans::read(DEV_ADDR, vec![0; 20]));
+    transactions.push(I2cTrans::read(DEV_ADDR, [0; 32].to_vec()));
+    transactions.push(I2cTrans::write(DEV_ADDR, vec![0, 0]));
+    let mut dev = new_si4703(&transactions);
+    for _ in 0..20 {
+        let result = dev.seek(SeekMode::NoWrap, SeekDirection::Down);
+        assert!(matches!(result, Err(nb::Error::WouldBlock)));
+    }
+    let result = dev.seek(SeekMode::NoWrap, SeekDirection::Down);
+    assert!(matches!(result, Err(nb::Error::Other(Error::Aborted))));
+    // the shadow copy is read again
+    dev.mute().unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn stc_without_seek_is_aborted() {
+    let mut stc_data = [0; 32];
+    stc_data[0] = (BF::STC >> 8) as u8;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, stc_data.to_vec()),
+        I2cTrans::read(DEV_ADDR, stc_data.to_vec()),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let result = dev.seek(SeekMode::NoWrap, SeekDirection::Down);
+    assert!(matches!(result, Err(nb::Error::Other(Error::Aborted))));
+    assert!(!dev.is_busy());
+    destroy(dev);
+}
+
+#[test]
+fn can_complete_seek_not_started_by_driver() {
+    let mut found_data = [0; 32];
+    found_data[0] = (BF::STC >> 8) as u8;
+    found_data[1] = BF::STC as u8;
+    let mut seeking_found_data = found_data;
+    seeking_found_data[16] = (BF::SEEK >> 8) as u8;
+    seeking_found_data[17] = BF::SEEK as u8;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, seeking_found_data.to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0]),
+        I2cTrans::read(DEV_ADDR, found_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, [0; 32][..4].to_vec()),
     ];
     let mut dev = new_si4703(&transactions);
     block!(dev.seek(SeekMode::NoWrap, SeekDirection::Down)).unwrap();
     destroy(dev);
 }
 
-fn fail_seeking_test(seeking_found_statusrssi: u16) {
+fn fail_seeking_test(seeking_found_statusrssi: u16, band_limit: bool, afc_railed: bool) {
     let mut found_data = [0; 32];
     found_data[0] = (BF::STC >> 8) as u8;
     found_data[1] = BF::STC as u8;
@@ -132,30 +335,30 @@ fn fail_seeking_test(seeking_found_statusrssi: u16) {
     let transactions = [
         I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
         I2cTrans::write(DEV_ADDR, vec![(BF::SEEK >> 8) as u8, BF::SEEK as u8]),
-        I2cTrans::read(DEV_ADDR, seeking_data.to_vec()),
-        I2cTrans::read(DEV_ADDR, seeking_found_data.to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_found_data[..4].to_vec()),
         I2cTrans::write(DEV_ADDR, vec![0, 0]),
-        I2cTrans::read(DEV_ADDR, found_data.to_vec()),
-        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::read(DEV_ADDR, found_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, [0; 32][..4].to_vec()),
     ];
     let mut dev = new_si4703(&transactions);
-    assert_error!(
-        block!(dev.seek(SeekMode::NoWrap, SeekDirection::Down)),
-        Error::SeekFailed
-    );
+    let outcome = block!(dev.seek(SeekMode::NoWrap, SeekDirection::Down)).unwrap();
+    assert!(!outcome.found());
+    assert_eq!(band_limit, outcome.band_limit);
+    assert_eq!(afc_railed, outcome.afc_railed);
     destroy(dev);
 }
 
 #[test]
 fn can_fail_seeking() {
     let statusrssi = BF::STC | BF::SF_BL;
-    fail_seeking_test(statusrssi);
+    fail_seeking_test(statusrssi, true, false);
 }
 
 #[test]
 fn can_fail_seeking_afc_railed() {
     let statusrssi = BF::STC | BF::AFCRL;
-    fail_seeking_test(statusrssi);
+    fail_seeking_test(statusrssi, false, true);
 }
 
 #[test]
@@ -186,12 +389,12 @@ fn can_seek_with_stc_int_pin() {
                 BF::STCIEN as u8 | 1 << 2,
             ],
         ),
-        I2cTrans::read(DEV_ADDR, seeking_data.to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_data[..4].to_vec()),
         // this time STC bit is (incorrectly) not set
-        I2cTrans::read(DEV_ADDR, seeking_found_data.to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_found_data[..4].to_vec()),
         I2cTrans::write(DEV_ADDR, vec![0, 0]),
-        I2cTrans::read(DEV_ADDR, found_data.to_vec()),
-        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::read(DEV_ADDR, found_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, [0; 32][..4].to_vec()),
     ];
     let pin_trans = [
         PinTrans::get(PinState::High),
@@ -200,12 +403,16 @@ fn can_seek_with_stc_int_pin() {
     ];
     let mut pin = PinMock::new(&pin_trans);
     let mut dev = new_si4703(&transactions);
-    block!(dev.seek_with_stc_int_pin(SeekMode::NoWrap, SeekDirection::Down, &pin)).unwrap();
+    block!(dev.seek_with_stc_int_pin(SeekMode::NoWrap, SeekDirection::Down, &mut pin)).unwrap();
     destroy(dev);
     pin.done()
 }
 
-fn fail_seeking_with_stc_int_pin_test(seeking_found_statusrssi: u16) {
+fn fail_seeking_with_stc_int_pin_test(
+    seeking_found_statusrssi: u16,
+    band_limit: bool,
+    afc_railed: bool,
+) {
     let mut found_data = [0; 32];
     found_data[0] = (BF::STC >> 8) as u8;
     found_data[1] = BF::STC as u8;
@@ -232,12 +439,12 @@ fn fail_seeking_with_stc_int_pin_test(seeking_found_statusrssi: u16) {
                 BF::STCIEN as u8 | 1 << 2,
             ],
         ),
-        I2cTrans::read(DEV_ADDR, seeking_data.to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_data[..4].to_vec()),
         // this time STC bit is (incorrectly) not set
-        I2cTrans::read(DEV_ADDR, seeking_found_data.to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_found_data[..4].to_vec()),
         I2cTrans::write(DEV_ADDR, vec![0, 0]),
-        I2cTrans::read(DEV_ADDR, found_data.to_vec()),
-        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::read(DEV_ADDR, found_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, [0; 32][..4].to_vec()),
     ];
     let pin_trans = [
         PinTrans::get(PinState::High),
@@ -246,23 +453,23 @@ fn fail_seeking_with_stc_int_pin_test(seeking_found_statusrssi: u16) {
     ];
     let mut pin = PinMock::new(&pin_trans);
     let mut dev = new_si4703(&transactions);
-    assert_error!(
-        block!(dev.seek_with_stc_int_pin(SeekMode::NoWrap, SeekDirection::Down, &pin)),
-        ErrorWithPin::SeekFailed
-    );
+    let outcome =
+        block!(dev.seek_with_stc_int_pin(SeekMode::NoWrap, SeekDirection::Down, &mut pin)).unwrap();
+    assert!(!outcome.found());
+    assert_eq!(band_limit, outcome.band_limit);
+    assert_eq!(afc_railed, outcome.afc_railed);
     destroy(dev);
     pin.done();
 }
 
 #[test]
-
 fn can_fail_seeking_with_stc_int_pin() {
     let statusrssi = BF::STC | BF::SF_BL;
-    fail_seeking_with_stc_int_pin_test(statusrssi);
+    fail_seeking_with_stc_int_pin_test(statusrssi, true, false);
 }
 
 #[test]
 fn can_fail_seeking_with_stc_int_pin_afc_railed() {
     let statusrssi = BF::STC | BF::AFCRL;
-    fail_seeking_with_stc_int_pin_test(statusrssi);
+    fail_seeking_with_stc_int_pin_test(statusrssi, false, true);
 }
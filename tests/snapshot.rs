@@ -0,0 +1,185 @@
+use embedded_hal_mock::eh1::{
+    delay::{CheckedDelay, Transaction as DelayTrans},
+    i2c::Transaction as I2cTrans,
+};
+use si4703::{
+    registers::{Channel, PowerCfg, Test1},
+    Error, Snapshot,
+};
+
+mod common;
+use self::common::{destroy, new_si4703, BitFlags as BF, Register, DEV_ADDR};
+
+const STATUSRSSI: usize = 0xA;
+const READCHAN: usize = 0xB;
+
+fn read_data(regs: &[u16; 16]) -> Vec<u8> {
+    (0..16)
+        .map(|i| regs[(i + 0xA) % 16])
+        .flat_map(|reg| vec![(reg >> 8) as u8, reg as u8])
+        .collect()
+}
+
+fn running_registers() -> [u16; 16] {
+    let mut regs = [0; 16];
+    regs[Register::POWERCFG] = BF::DMUTE | BF::ENABLE;
+    regs[Register::CHANNEL] = 5;
+    regs[Register::SYSCONFIG2] = 0x1F;
+    regs[Register::TEST1] = BF::XOSCEN;
+    regs[READCHAN] = 5;
+    regs
+}
+
+const RUNNING_SNAPSHOT: [u8; Snapshot::SIZE] = [0x40, 1, 0, 5, 0, 0, 0, 0x1F, 0, 0, 0x80, 0];
+
+#[test]
+fn can_take_snapshot() {
+    let mut regs = running_registers();
+    regs[Register::POWERCFG] |= BF::SEEK;
+    regs[READCHAN] = 0xC005;
+    let transactions = [I2cTrans::read(DEV_ADDR, read_data(&regs))];
+    let mut dev = new_si4703(&transactions);
+    let snapshot = dev.snapshot().unwrap();
+    assert_eq!(5, snapshot.channel());
+    assert!(snapshot.get::<PowerCfg>().enable());
+    assert!(!snapshot.get::<PowerCfg>().seek());
+    assert_eq!(5, snapshot.get::<Channel>().chan());
+    assert!(snapshot.get::<Test1>().xoscen());
+    assert_eq!(RUNNING_SNAPSHOT, snapshot.to_bytes());
+    destroy(dev);
+}
+
+#[test]
+fn snapshot_from_bytes_has_no_ongoing_operation() {
+    let snapshot = Snapshot::from_bytes(&[0x41, 1, 0x80, 5, 0, 0, 0, 0x1F, 0, 0, 0x80, 0]);
+    assert_eq!(5, snapshot.channel());
+    assert!(!snapshot.get::<PowerCfg>().seek());
+    assert!(!snapshot.get::<Channel>().tune());
+    assert_eq!(RUNNING_SNAPSHOT, snapshot.to_bytes());
+}
+
+#[test]
+fn can_restore_disabled_snapshot() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 7, 0, 0, 0, 0x1F, 0, 0, 0, 0]),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[]);
+    let snapshot = Snapshot::from_bytes(&[0, 0, 0, 7, 0, 0, 0, 0x1F, 0, 0, 0, 0]);
+    dev.restore(&snapshot, &mut delay).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_restore_after_reset() {
+    let mut tuning = running_registers();
+    tuning[Register::CHANNEL] |= BF::TUNE;
+    tuning[STATUSRSSI] = BF::STC;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        // enable oscillator first
+        I2cTrans::write(DEV_ADDR, vec![0x40, 0, 0, 5, 0, 0, 0, 0x1F, 0, 0, 0x80, 0]),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 5, 0, 0, 0, 0x1F, 0, 0, 0x80, 0]),
+        // tune
+        I2cTrans::read(DEV_ADDR, read_data(&running_registers())[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0x80, 5]),
+        I2cTrans::read(DEV_ADDR, read_data(&tuning)[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 5]),
+        I2cTrans::read(DEV_ADDR, read_data(&running_registers())[..4].to_vec()),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::blocking_delay_ms(500),
+        DelayTrans::blocking_delay_ms(110),
+        DelayTrans::blocking_delay_ms(10),
+        DelayTrans::blocking_delay_ms(10),
+    ]);
+    let snapshot = Snapshot::from_bytes(&RUNNING_SNAPSHOT);
+    dev.restore(&snapshot, &mut delay).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn restore_on_running_device_does_not_wait_for_powerup() {
+    let mut tuning = running_registers();
+    tuning[Register::CHANNEL] |= BF::TUNE;
+    tuning[STATUSRSSI] = BF::STC;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, read_data(&running_registers())),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 5, 0, 0, 0, 0x1F, 0, 0, 0x80, 0]),
+        I2cTrans::read(DEV_ADDR, read_data(&running_registers())[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0x80, 5]),
+        I2cTrans::read(DEV_ADDR, read_data(&tuning)[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 5]),
+        I2cTrans::read(DEV_ADDR, read_data(&running_registers())[..4].to_vec()),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[
+        DelayTrans::blocking_delay_ms(10),
+        DelayTrans::blocking_delay_ms(10),
+    ]);
+    let snapshot = Snapshot::from_bytes(&RUNNING_SNAPSHOT);
+    dev.restore(&snapshot, &mut delay).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn snapshot_after_disable_is_restored_disabled() {
+    let mut disabled = running_registers();
+    disabled[Register::POWERCFG] |= BF::DISABLE;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, read_data(&disabled)),
+        I2cTrans::read(DEV_ADDR, read_data(&running_registers())),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0x40, 0x41, 0, 5, 0, 0, 0, 0x1F, 0, 0, 0x80, 0],
+        ),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&[]);
+    let snapshot = dev.snapshot().unwrap();
+    assert!(snapshot.get::<PowerCfg>().disable());
+    dev.restore(&snapshot, &mut delay).unwrap();
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn restore_times_out_if_tuning_does_not_finish() {
+    let mut tuning = running_registers();
+    tuning[Register::CHANNEL] |= BF::TUNE;
+    let mut transactions = vec![
+        I2cTrans::read(DEV_ADDR, read_data(&running_registers())),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 5, 0, 0, 0, 0x1F, 0, 0, 0x80, 0]),
+        I2cTrans::read(DEV_ADDR, read_data(&running_registers())[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0x80, 5]),
+    ];
+    for _ in 0..2 {
+        transactions.extend(vec![
+            I2cTrans::read(
+                DEV_ADDR,
+                read_data(&tuning)[..4].to_vec()
+            );
+            9
+        ]);
+        transactions.push(I2cTrans::read(DEV_ADDR, read_data(&tuning)[..20].to_vec()));
+    }
+    // cancel the tuning
+    transactions.extend([
+        I2cTrans::read(DEV_ADDR, read_data(&tuning)[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0x40, 1, 0, 5]),
+        I2cTrans::read(DEV_ADDR, read_data(&running_registers())[..4].to_vec()),
+    ]);
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&vec![DelayTrans::blocking_delay_ms(10); 21]);
+    let snapshot = Snapshot::from_bytes(&RUNNING_SNAPSHOT);
+    let result = dev.restore(&snapshot, &mut delay);
+    assert!(matches!(result, Err(Error::Timeout)));
+    assert!(!dev.is_busy());
+    delay.done();
+    destroy(dev);
+}
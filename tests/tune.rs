@@ -1,9 +1,10 @@
-use embedded_hal_mock::{
+use embedded_hal_mock::eh1::{
+    delay::{CheckedDelay, Transaction as DelayTrans},
+    digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
     i2c::Transaction as I2cTrans,
-    pin::{Mock as PinMock, State as PinState, Transaction as PinTrans},
 };
 use nb::block;
-use si4703::TuneChannel;
+use si4703::{Error, Frequency, Rounding, TuneChannel, TuneOutcome};
 
 mod common;
 use self::common::{destroy, new_si4703, BitFlags as BF, DEV_ADDR};
@@ -27,17 +28,187 @@ fn can_tune() {
             DEV_ADDR,
             vec![0, 0, (BF::TUNE >> 8) as u8, BF::TUNE as u8 | 2],
         ),
-        I2cTrans::read(DEV_ADDR, seeking_data.to_vec()),
-        I2cTrans::read(DEV_ADDR, seeking_found_data.to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_found_data[..4].to_vec()),
         I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 2]),
-        I2cTrans::read(DEV_ADDR, found_data.to_vec()),
-        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::read(DEV_ADDR, found_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, [0; 32][..4].to_vec()),
     ];
     let mut dev = new_si4703(&transactions);
     block!(dev.tune(TuneChannel::Raw(2))).unwrap();
     destroy(dev);
 }
 
+#[test]
+fn tune_reports_afc_rail() {
+    let mut seeking_found_data = [0; 32];
+    seeking_found_data[0] = ((BF::STC | BF::AFCRL) >> 8) as u8;
+    seeking_found_data[1] = 0x08;
+    seeking_found_data[3] = 2;
+    seeking_found_data[18] = (BF::TUNE >> 8) as u8;
+    seeking_found_data[19] = BF::TUNE as u8 | 2;
+    let mut tuned_data = [0; 32];
+    tuned_data[3] = 2;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, (BF::TUNE >> 8) as u8, BF::TUNE as u8 | 2],
+        ),
+        I2cTrans::read(DEV_ADDR, seeking_found_data[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 2]),
+        I2cTrans::read(DEV_ADDR, tuned_data[..4].to_vec()),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let outcome = block!(dev.tune(TuneChannel::Khz(87_900))).unwrap();
+    assert_eq!(
+        TuneOutcome {
+            frequency: Frequency::from_khz(87_900),
+            rssi: 0x08,
+            stereo: false,
+            afc_railed: true,
+        },
+        outcome
+    );
+    destroy(dev);
+}
+
+#[test]
+fn can_tune_rounded() {
+    let mut seeking_found_data = [0; 32];
+    seeking_found_data[0] = ((BF::STC | BF::ST) >> 8) as u8;
+    seeking_found_data[1] = 0x25;
+    seeking_found_data[3] = 3;
+    seeking_found_data[18] = (BF::TUNE >> 8) as u8;
+    seeking_found_data[19] = BF::TUNE as u8 | 3;
+    let mut tuned_data = [0; 32];
+    tuned_data[3] = 3;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, (BF::TUNE >> 8) as u8, BF::TUNE as u8 | 3],
+        ),
+        I2cTrans::read(DEV_ADDR, seeking_found_data[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 3]),
+        I2cTrans::read(DEV_ADDR, tuned_data[..4].to_vec()),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let outcome = block!(dev.tune_rounded(TuneChannel::Khz(88_050), Rounding::Nearest)).unwrap();
+    assert_eq!(
+        TuneOutcome {
+            frequency: Frequency::from_khz(88_100),
+            rssi: 0x25,
+            stereo: true,
+            afc_railed: false,
+        },
+        outcome
+    );
+    destroy(dev);
+}
+
+#[test]
+fn can_tune_blocking() {
+    let mut tuning_data = [0; 32];
+    tuning_data[18] = (BF::TUNE >> 8) as u8;
+    tuning_data[19] = BF::TUNE as u8 | 2;
+    let mut tuned_data = tuning_data;
+    tuned_data[0] = (BF::STC >> 8) as u8;
+    tuned_data[1] = BF::STC as u8;
+    tuned_data[3] = 2;
+    let mut done_data = [0; 32];
+    done_data[3] = 2;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, (BF::TUNE >> 8) as u8, BF::TUNE as u8 | 2],
+        ),
+        I2cTrans::read(DEV_ADDR, tuning_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, tuned_data[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 2]),
+        I2cTrans::read(DEV_ADDR, done_data[..4].to_vec()),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&vec![DelayTrans::blocking_delay_ms(10); 3]);
+    let outcome = dev
+        .tune_blocking(TuneChannel::Khz(87_900), &mut delay, 100)
+        .unwrap();
+    assert_eq!(Frequency::from_khz(87_900), outcome.frequency);
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn tune_blocking_times_out() {
+    let mut tuning_data = [0; 32];
+    tuning_data[18] = (BF::TUNE >> 8) as u8;
+    tuning_data[19] = BF::TUNE as u8 | 2;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, (BF::TUNE >> 8) as u8, BF::TUNE as u8 | 2],
+        ),
+        I2cTrans::read(DEV_ADDR, tuning_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, tuning_data[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 2]),
+        I2cTrans::read(DEV_ADDR, [0; 4].to_vec()),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let mut delay = CheckedDelay::new(&vec![DelayTrans::blocking_delay_ms(10); 2]);
+    let result = dev.tune_blocking(TuneChannel::Raw(2), &mut delay, 10);
+    assert!(matches!(result, Err(Error::Timeout)));
+    delay.done();
+    destroy(dev);
+}
+
+#[test]
+fn can_cancel_tune() {
+    let mut tuning_data = [0; 32];
+    tuning_data[18] = (BF::TUNE >> 8) as u8;
+    tuning_data[19] = BF::TUNE as u8 | 2;
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, (BF::TUNE >> 8) as u8, BF::TUNE as u8 | 2],
+        ),
+        I2cTrans::read(DEV_ADDR, tuning_data[..4].to_vec()),
+        I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 2]),
+        I2cTrans::read(DEV_ADDR, [0; 4].to_vec()),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let result = dev.tune(TuneChannel::Raw(2));
+    assert!(matches!(result, Err(nb::Error::WouldBlock)));
+    block!(dev.cancel_tune()).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn tune_off_grid_returns_error() {
+    let transactions = [I2cTrans::read(DEV_ADDR, [0; 32].to_vec())];
+    let mut dev = new_si4703(&transactions);
+    let result = dev.tune_rounded(TuneChannel::Khz(88_050), Rounding::Strict);
+    assert!(matches!(result, Err(nb::Error::Other(Error::OffGrid))));
+    destroy(dev);
+}
+
+#[test]
+fn can_start_tune_by_khz() {
+    let transactions = [
+        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![0, 0, (BF::TUNE >> 8) as u8, BF::TUNE as u8 | 2],
+        ),
+    ];
+    let mut dev = new_si4703(&transactions);
+    let result = dev.tune(TuneChannel::Khz(87_900));
+    assert!(matches!(result, Err(nb::Error::WouldBlock)));
+    destroy(dev);
+}
+
 #[test]
 fn can_tune_with_stc_int_pin() {
     let mut found_data = [0; 32];
@@ -66,12 +237,12 @@ fn can_tune_with_stc_int_pin() {
                 BF::STCIEN as u8 | 1 << 2,
             ],
         ),
-        I2cTrans::read(DEV_ADDR, seeking_data.to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_data[..4].to_vec()),
         // this time STC bit is (incorrectly) not set
-        I2cTrans::read(DEV_ADDR, seeking_found_data.to_vec()),
+        I2cTrans::read(DEV_ADDR, seeking_found_data[..4].to_vec()),
         I2cTrans::write(DEV_ADDR, vec![0, 0, 0, 2]),
-        I2cTrans::read(DEV_ADDR, found_data.to_vec()),
-        I2cTrans::read(DEV_ADDR, [0; 32].to_vec()),
+        I2cTrans::read(DEV_ADDR, found_data[..4].to_vec()),
+        I2cTrans::read(DEV_ADDR, [0; 32][..4].to_vec()),
     ];
     let pin_trans = [
         PinTrans::get(PinState::High),
@@ -80,7 +251,7 @@ fn can_tune_with_stc_int_pin() {
     ];
     let mut pin = PinMock::new(&pin_trans);
     let mut dev = new_si4703(&transactions);
-    block!(dev.tune_with_stc_int_pin(TuneChannel::Raw(2), &pin)).unwrap();
+    block!(dev.tune_with_stc_int_pin(TuneChannel::Raw(2), &mut pin)).unwrap();
     destroy(dev);
     pin.done()
 }